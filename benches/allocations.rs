@@ -0,0 +1,87 @@
+//! Counts the allocations made by a request handler call, and times it,
+//! comparing the `RequestHandler` with the `UnboxedRequestHandler`.
+//!
+//! Run with `cargo bench --bench allocations`.
+
+use brazier::*;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const CALLS: usize = 100_000;
+
+pub struct Add(u64, u64);
+
+impl Request<u64> for Add {}
+
+pub struct AddHandler;
+
+#[async_trait::async_trait]
+impl RequestHandler<Add, u64> for AddHandler {
+    async fn handle(&mut self, request: Add) -> Result<u64> {
+        Ok(request.0 + request.1)
+    }
+}
+
+impl UnboxedRequestHandler<Add, u64> for AddHandler {
+    async fn handle(&mut self, request: Add) -> Result<u64> {
+        Ok(request.0 + request.1)
+    }
+}
+
+/// Runs the call `CALLS` times, then prints the number of allocations and the time per call.
+macro_rules! count {
+    ($name:expr, |$i:ident| $call:expr) => {{
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for $i in 0..CALLS as u64 {
+            std::hint::black_box($call.await.unwrap());
+        }
+        let elapsed = start.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!(
+            "{:<32} {:>6.2} allocations per call {:>8.1} ns per call",
+            $name,
+            allocations as f64 / CALLS as f64,
+            elapsed.as_nanos() as f64 / CALLS as f64
+        );
+    }};
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut handler = AddHandler;
+    count!("RequestHandler::handle", |i| RequestHandler::handle(
+        &mut handler,
+        Add(i, 1)
+    ));
+    count!("UnboxedRequestHandler::handle", |i| {
+        UnboxedRequestHandler::handle(&mut handler, Add(i, 1))
+    });
+    let mut mediator = Mediator::new();
+    mediator.register_handler(AddHandler);
+    count!("Mediator::send, boxed", |i| mediator.send(Add(i, 1)));
+    let mut mediator = Mediator::new();
+    mediator.register_unboxed_handler(AddHandler);
+    count!("Mediator::send, unboxed", |i| mediator.send(Add(i, 1)));
+}
@@ -0,0 +1,85 @@
+//! Measures the throughput of requests sent concurrently from many tasks,
+//! comparing the `SharedMediator`, behind a mutex, with the `ConcurrentMediator`,
+//! behind a read-write lock.
+//!
+//! Run with `cargo bench --bench concurrency`.
+
+use brazier::*;
+use futures::lock::Mutex;
+use std::time::{Duration, Instant};
+
+const TASKS: usize = 16;
+const REQUESTS_PER_TASK: usize = 50;
+
+pub struct Lookup(u64);
+
+impl Request<u64> for Lookup {}
+
+/// A handler waiting on simulated I/O, e.g. a database lookup.
+pub struct LookupHandler;
+
+#[async_trait::async_trait]
+impl RequestHandler<Lookup, u64> for LookupHandler {
+    async fn handle(&mut self, request: Lookup) -> Result<u64> {
+        tokio::time::sleep(Duration::from_micros(200)).await;
+        Ok(request.0)
+    }
+}
+
+#[async_trait::async_trait]
+impl ReadHandler<Lookup, u64> for LookupHandler {
+    async fn handle(&self, request: Lookup) -> Result<u64> {
+        tokio::time::sleep(Duration::from_micros(200)).await;
+        Ok(request.0)
+    }
+}
+
+/// Sends `REQUESTS_PER_TASK` requests from each of `TASKS` tasks,
+/// then prints the number of requests handled per second.
+macro_rules! throughput {
+    ($name:expr, $mediator:expr, |$m:ident, $i:ident| $call:expr) => {{
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..TASKS)
+            .map(|_| {
+                let $m = $mediator.clone();
+                tokio::spawn(async move {
+                    for $i in 0..REQUESTS_PER_TASK as u64 {
+                        std::hint::black_box($call.await.unwrap());
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "{:<40} {:>10.0} requests per second",
+            $name,
+            (TASKS * REQUESTS_PER_TASK) as f64 / elapsed.as_secs_f64()
+        );
+    }};
+}
+
+#[tokio::main]
+async fn main() {
+    let mut mediator = Mediator::new();
+    mediator.register_handler(LookupHandler);
+    let shared = SharedMediator::new(mediator);
+    throughput!("SharedMediator::send", shared, |m, i| m.send(Lookup(i)));
+
+    let concurrent = ConcurrentMediator::default();
+    concurrent.write().register_read_handler(LookupHandler);
+    throughput!("ConcurrentMediator::query", concurrent, |m, i| m
+        .query(Lookup(i)));
+
+    let concurrent = ConcurrentMediator::default();
+    concurrent
+        .write()
+        .register_read_handler(Mutex::new(LookupHandler));
+    throughput!(
+        "ConcurrentMediator::query, mutex handler",
+        concurrent,
+        |m, i| m.query(Lookup(i))
+    );
+}
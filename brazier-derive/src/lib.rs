@@ -0,0 +1,265 @@
+//! Derive macros for [brazier](https://github.com/yves-bonami/brazier).
+
+#![warn(missing_docs)]
+#![deny(unsafe_code)]
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, DeriveInput, FnArg, GenericArgument, ImplItem, ItemImpl, LitBool,
+    PathArguments, ReturnType, Type,
+};
+
+/// Derives the `Request` trait, with the response type given by the `#[response(...)]` attribute.
+///
+/// # Example
+///
+/// ```rust
+/// use brazier::Request;
+///
+/// #[derive(Request)]
+/// #[response(String)]
+/// pub struct Ping;
+/// ```
+///
+/// The `#[response(...)]` attribute is required:
+///
+/// ```compile_fail
+/// use brazier::Request;
+///
+/// #[derive(Request)]
+/// pub struct Ping;
+/// ```
+///
+/// A request derives a single response type, so a second attribute is rejected:
+///
+/// ```compile_fail
+/// use brazier::Request;
+///
+/// #[derive(Request)]
+/// #[response(String)]
+/// #[response(u64)]
+/// pub struct Ping;
+/// ```
+#[proc_macro_derive(Request, attributes(response))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_request(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_request(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut responses = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("response"));
+    let response = match (responses.next(), responses.next()) {
+        (Some(attr), None) => attr.parse_args::<Type>()?,
+        (Some(_), Some(duplicate)) => {
+            return Err(syn::Error::new_spanned(
+                duplicate,
+                "duplicate `#[response(...)]` attribute",
+            ))
+        }
+        (None, _) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "missing `#[response(...)]` attribute, e.g. `#[response(String)]`",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::brazier::Request<#response> for #name #ty_generics #where_clause {}
+    })
+}
+
+/// Implements `RequestHandler` from an impl block holding a single
+/// `async fn handle(&mut self, request: TRequest) -> Result<TResponse>`,
+/// along with `Request<TResponse>` for the request type, unless `request = false` is given.
+///
+/// # Example
+///
+/// ```rust
+/// use brazier::{handler, Mediator, Result};
+///
+/// pub struct Ping;
+///
+/// pub struct PingHandler;
+///
+/// #[handler]
+/// impl PingHandler {
+///     async fn handle(&mut self, _request: Ping) -> Result<String> {
+///         Ok(String::from("pong!"))
+///     }
+/// }
+///
+/// # async fn run() -> Result<()> {
+/// let mut mediator = Mediator::new();
+/// mediator.register_handler(PingHandler);
+/// assert_eq!(mediator.send(Ping).await?, "pong!");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The generated `Request` impl conflicts with any other impl of `Request<TResponse>` for the
+/// request type, e.g. from `#[derive(Request)]`, a hand-written impl or a second `#[handler]`
+/// for the same request, e.g. for keyed handlers:
+///
+/// ```compile_fail
+/// use brazier::{handler, Request, Result};
+///
+/// #[derive(Request)]
+/// #[response(String)]
+/// pub struct Ping;
+///
+/// pub struct PingHandler;
+///
+/// #[handler]
+/// impl PingHandler {
+///     async fn handle(&mut self, _request: Ping) -> Result<String> {
+///         Ok(String::from("pong!"))
+///     }
+/// }
+/// ```
+///
+/// `#[handler(request = false)]` only implements `RequestHandler`, leaving the `Request` impl
+/// to the request type, which is also required for a request type defined in another crate:
+///
+/// ```rust
+/// use brazier::{handler, Request, Result};
+///
+/// #[derive(Request)]
+/// #[response(String)]
+/// pub struct Ping;
+///
+/// pub struct PingHandler;
+///
+/// #[handler(request = false)]
+/// impl PingHandler {
+///     async fn handle(&mut self, _request: Ping) -> Result<String> {
+///         Ok(String::from("pong!"))
+///     }
+/// }
+/// ```
+///
+/// The method has to take `&mut self` and the request, and return a `Result`:
+///
+/// ```compile_fail
+/// use brazier::{handler, Result};
+///
+/// pub struct Ping;
+///
+/// pub struct PingHandler;
+///
+/// #[handler]
+/// impl PingHandler {
+///     async fn handle(&self, _request: Ping) -> Result<String> {
+///         Ok(String::from("pong!"))
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut impl_request = true;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("request") {
+            impl_request = meta.value()?.parse::<LitBool>()?.value;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `#[handler]` argument, expected `request = false`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+    let input = parse_macro_input!(item as ItemImpl);
+    match expand_handler(input, impl_request) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+const HANDLER_SIGNATURE: &str =
+    "expected `async fn handle(&mut self, request: TRequest) -> Result<TResponse>`";
+
+fn expand_handler(
+    mut input: ItemImpl,
+    impl_request: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`#[handler]` expects an impl block without a trait, e.g. `impl PingHandler`",
+        ));
+    }
+    let method = match input.items.as_slice() {
+        [ImplItem::Fn(method)] if method.sig.ident == "handle" => method,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.self_ty,
+                format!("{} as the only item of the impl block", HANDLER_SIGNATURE),
+            ))
+        }
+    };
+    let sig = &method.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(sig.fn_token, HANDLER_SIGNATURE));
+    }
+    let request = match (
+        sig.inputs.first(),
+        sig.inputs.iter().nth(1),
+        sig.inputs.len(),
+    ) {
+        (Some(FnArg::Receiver(receiver)), Some(FnArg::Typed(request)), 2)
+            if receiver.reference.is_some() && receiver.mutability.is_some() =>
+        {
+            (*request.ty).clone()
+        }
+        _ => return Err(syn::Error::new_spanned(&sig.inputs, HANDLER_SIGNATURE)),
+    };
+    let response = response_type(&sig.output)
+        .ok_or_else(|| syn::Error::new_spanned(&sig.output, HANDLER_SIGNATURE))?;
+
+    input.trait_ = Some((
+        None,
+        syn::parse_quote!(::brazier::RequestHandler<#request, #response>),
+        Default::default(),
+    ));
+    input
+        .attrs
+        .push(syn::parse_quote!(#[::brazier::__private::async_trait]));
+    if !impl_request {
+        return Ok(quote!(#input));
+    }
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::brazier::Request<#response> for #request #where_clause {}
+
+        #input
+    })
+}
+
+/// Returns `TResponse` from a `Result<TResponse>` return type.
+fn response_type(output: &ReturnType) -> Option<Type> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => return None,
+    };
+    let segment = match &**ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
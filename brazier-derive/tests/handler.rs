@@ -0,0 +1,87 @@
+use brazier::{handler, Mediator, Request, Result};
+
+pub struct Add(i64, i64);
+
+#[derive(Debug, Default)]
+pub struct AddHandler {
+    calls: usize,
+}
+
+#[handler]
+impl AddHandler {
+    /// Adds the two numbers.
+    async fn handle(&mut self, request: Add) -> Result<i64> {
+        self.calls += 1;
+        Ok(request.0 + request.1)
+    }
+}
+
+pub struct Greet<T>(T);
+
+pub struct GreetHandler;
+
+#[handler]
+impl GreetHandler {
+    async fn handle(&mut self, request: Greet<&'static str>) -> brazier::Result<String> {
+        Ok(format!("Hello, {}!", request.0))
+    }
+}
+
+pub struct Negate(i64);
+
+impl Request<i64> for Negate {}
+
+impl Request<String> for Negate {}
+
+pub struct NegateHandler;
+
+#[handler(request = false)]
+impl NegateHandler {
+    async fn handle(&mut self, request: Negate) -> Result<i64> {
+        Ok(-request.0)
+    }
+}
+
+pub struct NegateTextHandler;
+
+#[handler(request = false)]
+impl NegateTextHandler {
+    async fn handle(&mut self, request: Negate) -> Result<String> {
+        Ok((-request.0).to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_handler_without_request_impl() {
+    let mut mediator = Mediator::new();
+    mediator
+        .register_keyed_handler("number", NegateHandler)
+        .register_keyed_handler("text", NegateTextHandler);
+    assert_eq!(
+        mediator
+            .send_keyed::<_, i64>("number", Negate(1))
+            .await
+            .unwrap(),
+        -1
+    );
+    assert_eq!(
+        mediator
+            .send_keyed::<_, String>("text", Negate(1))
+            .await
+            .unwrap(),
+        "-1"
+    );
+}
+
+#[tokio::test]
+async fn test_handler() {
+    let mut mediator = Mediator::new();
+    mediator
+        .register_handler(AddHandler::default())
+        .register_handler(GreetHandler);
+    assert_eq!(mediator.send(Add(1, 2)).await.unwrap(), 3);
+    assert_eq!(
+        mediator.send(Greet("world")).await.unwrap(),
+        "Hello, world!"
+    );
+}
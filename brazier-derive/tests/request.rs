@@ -0,0 +1,23 @@
+use brazier::Request;
+
+fn assert_request<TRequest: Request<TResponse>, TResponse>() {}
+
+#[derive(Request)]
+#[response(String)]
+pub struct Ping;
+
+#[derive(Request)]
+#[response(Vec<T>)]
+pub struct List<T: Send + Sync + 'static> {
+    pub items: Vec<T>,
+}
+
+#[test]
+fn test_derive_request() {
+    assert_request::<Ping, String>();
+}
+
+#[test]
+fn test_derive_request_with_generics() {
+    assert_request::<List<u32>, Vec<u32>>();
+}
@@ -0,0 +1,111 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::MediatorError;
+
+/// A map storing one value per key type, as the mediator does for its handlers.
+///
+/// The key is a type, usually the request type, and the value can be of any type,
+/// which is checked when the value is read.
+#[derive(Debug, Default)]
+pub struct AnyMap(HashMap<TypeId, Box<dyn Any + Send + Sync>>);
+
+impl AnyMap {
+    /// Creates a new empty map.
+    pub fn new() -> Self {
+        AnyMap(HashMap::new())
+    }
+
+    /// Sets the value for the key type, replacing the previous one.
+    pub fn set<TKey: 'static, TValue: Any + Send + Sync>(&mut self, value: TValue) {
+        self.0.insert(TypeId::of::<TKey>(), Box::new(value));
+    }
+
+    /// Returns the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the map has no key.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if a value is set for the key type.
+    pub fn contains<TKey: 'static>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<TKey>())
+    }
+
+    /// Returns the value for the key type, or `None` if there is none of the given type.
+    pub fn get<TKey: 'static, TValue: Any + Send + Sync>(&self) -> Option<&TValue> {
+        self.0
+            .get(&TypeId::of::<TKey>())
+            .and_then(|v| v.downcast_ref::<TValue>())
+    }
+
+    /// Returns the value for the key type mutably.
+    ///
+    /// Returns `MediatorError::HandlerNotRegisteredError` if no value is set for the key,
+    /// and `MediatorError::HandlerTypeMismatchError` if the value is of another type.
+    pub fn get_mut<TKey: 'static, TValue: Any + Send + Sync>(
+        &mut self,
+    ) -> core::result::Result<&mut TValue, MediatorError> {
+        match self.0.get_mut(&TypeId::of::<TKey>()) {
+            Some(v) => v
+                .downcast_mut::<TValue>()
+                .ok_or(MediatorError::HandlerTypeMismatchError),
+            None => Err(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TKey>().into(),
+            )),
+        }
+    }
+
+    /// Removes and returns the value for the key type.
+    ///
+    /// A value of another type is left in the map, and `None` is returned.
+    pub fn remove<TKey: 'static, TValue: Any + Send + Sync>(&mut self) -> Option<TValue> {
+        let key = TypeId::of::<TKey>();
+        if !self.0.get(&key)?.is::<TValue>() {
+            return None;
+        }
+        self.0
+            .remove(&key)
+            .and_then(|v| v.downcast::<TValue>().ok())
+            .map(|v| *v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_any_map_remove() {
+        let mut map = AnyMap::new();
+        map.set::<u8, String>(String::from("value"));
+        assert_eq!(map.remove::<u8, i32>(), None);
+        assert!(map.contains::<u8>());
+        assert_eq!(map.remove::<u8, String>(), Some(String::from("value")));
+        assert!(map.is_empty());
+        assert_eq!(map.remove::<u8, String>(), None);
+    }
+
+    #[test]
+    fn test_any_map_overwrite() {
+        let mut map = AnyMap::new();
+        map.set::<u8, String>(String::from("first"));
+        map.set::<u8, i32>(42);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get::<u8, String>(), None);
+        *map.get_mut::<u8, i32>().unwrap() += 1;
+        assert_eq!(map.get::<u8, i32>(), Some(&43));
+        assert_eq!(
+            map.get_mut::<u16, i32>().err(),
+            Some(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<u16>().into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+
+use crate::{Mediator, Request, Result};
+
+/// The batch handler trait. A batch handler handles many requests of the same type at once,
+/// e.g. to fetch them in a single database round trip.
+#[async_trait]
+pub trait BatchHandler<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the requests.
+    /// It returns one result per request, in the order of the requests.
+    async fn handle(&mut self, requests: Vec<TRequest>) -> Vec<Result<TResponse>>;
+}
+
+impl Mediator {
+    /// Registers a batch handler.
+    pub fn register_batch_handler<TRequest, TBatchHandler, TResponse>(
+        &mut self,
+        handler: TBatchHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TBatchHandler: BatchHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        self.batch_handlers
+            .set::<TRequest, Box<dyn BatchHandler<TRequest, TResponse>>>(Box::new(handler));
+        self
+    }
+
+    /// Send many requests of the same type to the mediator, returning one result per request.
+    ///
+    /// The requests are passed at once to the batch handler of the request when one is
+    /// registered, without the processors and the pipeline behaviors. Otherwise, they are sent
+    /// one after the other through the pipeline, and an error does not stop the batch.
+    pub async fn send_batch<TRequest, TResponse>(
+        &mut self,
+        requests: Vec<TRequest>,
+    ) -> Vec<Result<TResponse>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        if let Ok(handler) = self
+            .batch_handlers
+            .get_mut::<TRequest, Box<dyn BatchHandler<TRequest, TResponse>>>()
+        {
+            return handler.handle(requests).await;
+        }
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.send(request).await);
+        }
+        responses
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    pub struct Double(i64);
+
+    impl Request<i64> for Double {}
+
+    #[derive(Debug, Default)]
+    pub struct DoubleBatchHandler(Arc<Mutex<Vec<usize>>>);
+
+    #[async_trait]
+    impl BatchHandler<Double, i64> for DoubleBatchHandler {
+        async fn handle(&mut self, requests: Vec<Double>) -> Vec<Result<i64>> {
+            self.0.lock().unwrap().push(requests.len());
+            requests.into_iter().map(|r| Ok(r.0 * 2)).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_batch() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Double| async move {
+            match request.0 {
+                0 => Err("zero".into()),
+                n => Ok(n * 2),
+            }
+        });
+        let responses = m.send_batch(vec![Double(1), Double(0), Double(3)]).await;
+        assert_eq!(responses[0].as_ref().unwrap(), &2);
+        assert!(responses[1].is_err());
+        assert_eq!(responses[2].as_ref().unwrap(), &6);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_batch_to_batch_handler() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_batch_handler(DoubleBatchHandler(batches.clone()));
+        let responses = m.send_batch(vec![Double(1), Double(2)]).await;
+        let responses: Vec<_> = responses.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(responses, vec![2, 4]);
+        assert_eq!(*batches.lock().unwrap(), vec![2]);
+    }
+}
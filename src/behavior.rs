@@ -0,0 +1,407 @@
+use async_trait::async_trait;
+use std::any::{Any, TypeId};
+
+use crate::{
+    named::AnyRequestHandler, Mediator, MediatorError, Request, RequestContext, RequestHandler,
+    Result,
+};
+
+/// The pipeline behavior trait. Behaviors wrap the handling of a request,
+/// which makes them suited for cross-cutting concerns like logging, validation or timing.
+#[async_trait]
+pub trait PipelineBehavior<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    /// Call `next.run(request)` to continue the pipeline.
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse>;
+}
+
+pub(crate) type Behaviors<TRequest, TResponse> =
+    Vec<Box<dyn PipelineBehavior<TRequest, TResponse>>>;
+
+/// The global pipeline behavior trait. Global behaviors wrap the handling of every request,
+/// so they see the request and the response as type-erased values.
+#[async_trait]
+pub trait GlobalPipelineBehavior: Send + Sync {
+    /// The method that handles the request.
+    /// Call `next.run(request)` to continue the pipeline.
+    async fn handle(
+        &mut self,
+        request: Box<dyn Any + Send>,
+        next: AnyNext<'_>,
+    ) -> Result<Box<dyn Any + Send>>;
+}
+
+#[derive(Default)]
+pub(crate) struct GlobalBehaviors(Vec<Box<dyn GlobalPipelineBehavior>>);
+
+impl std::fmt::Debug for GlobalBehaviors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GlobalBehaviors")
+            .field(&self.0.len())
+            .finish()
+    }
+}
+
+/// The remainder of the pipeline, ending with the request handler.
+pub struct Next<'a, TRequest, TResponse> {
+    behaviors: &'a mut [Box<dyn PipelineBehavior<TRequest, TResponse>>],
+    handler: &'a mut dyn RequestHandler<TRequest, TResponse>,
+    context: &'a RequestContext,
+}
+
+impl<'a, TRequest, TResponse> Next<'a, TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    pub(crate) fn new(
+        behaviors: &'a mut [Box<dyn PipelineBehavior<TRequest, TResponse>>],
+        handler: &'a mut dyn RequestHandler<TRequest, TResponse>,
+        context: &'a RequestContext,
+    ) -> Self {
+        Next {
+            behaviors,
+            handler,
+            context,
+        }
+    }
+
+    /// Returns the context the request was sent with.
+    pub fn context(&self) -> &'a RequestContext {
+        self.context
+    }
+
+    /// Runs the next behavior, or the request handler when no behavior is left.
+    pub async fn run(&mut self, request: TRequest) -> Result<TResponse> {
+        match self.behaviors.split_first_mut() {
+            Some((behavior, behaviors)) => {
+                behavior
+                    .handle(
+                        request,
+                        Next::new(behaviors, &mut *self.handler, self.context),
+                    )
+                    .await
+            }
+            None => {
+                self.handler
+                    .handle_with_context(request, self.context)
+                    .await
+            }
+        }
+    }
+}
+
+impl<TRequest, TResponse> std::fmt::Debug for Next<'_, TRequest, TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("behaviors", &self.behaviors.len())
+            .finish()
+    }
+}
+
+/// The remainder of the pipeline of a global behavior, ending with the behaviors
+/// of the request and the request handler.
+pub struct AnyNext<'a> {
+    request_type_name: &'static str,
+    types: (TypeId, TypeId),
+    behaviors: &'a mut [Box<dyn GlobalPipelineBehavior>],
+    inner: &'a mut dyn AnyRequestHandler,
+    context: &'a RequestContext,
+}
+
+impl<'a> AnyNext<'a> {
+    /// Returns the type name of the request.
+    pub fn request_type_name(&self) -> &'static str {
+        self.request_type_name
+    }
+
+    /// Returns the type ids of the request and of its response.
+    pub(crate) fn type_ids(&self) -> (TypeId, TypeId) {
+        self.types
+    }
+
+    /// Returns the context the request was sent with.
+    pub fn context(&self) -> &'a RequestContext {
+        self.context
+    }
+
+    /// Runs the next global behavior, or the rest of the pipeline when no global behavior is left.
+    ///
+    /// Returns `MediatorError::RequestTypeMismatchError` if the request has been replaced
+    /// by a value of another type.
+    pub async fn run(&mut self, request: Box<dyn Any + Send>) -> Result<Box<dyn Any + Send>> {
+        match self.behaviors.split_first_mut() {
+            Some((behavior, behaviors)) => {
+                let next = AnyNext {
+                    request_type_name: self.request_type_name,
+                    types: self.types,
+                    behaviors,
+                    inner: &mut *self.inner,
+                    context: self.context,
+                };
+                behavior.handle(request, next).await
+            }
+            None => self.inner.handle(request).await,
+        }
+    }
+}
+
+impl std::fmt::Debug for AnyNext<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyNext")
+            .field("request_type_name", &self.request_type_name)
+            .field("behaviors", &self.behaviors.len())
+            .finish()
+    }
+}
+
+/// Adapts the typed remainder of the pipeline to the type-erased global behaviors.
+struct TypedNext<'a, TRequest, TResponse>(Next<'a, TRequest, TResponse>);
+
+#[async_trait]
+impl<TRequest, TResponse> AnyRequestHandler for TypedNext<'_, TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    async fn handle(&mut self, request: Box<dyn Any + Send>) -> Result<Box<dyn Any + Send>> {
+        let request = match request.downcast::<TRequest>() {
+            Ok(request) => *request,
+            Err(_) => return Err(Box::new(MediatorError::RequestTypeMismatchError)),
+        };
+        let response = self.0.run(request).await?;
+        Ok(Box::new(response))
+    }
+}
+
+/// Runs the request through the global behaviors, then through the typed pipeline.
+pub(crate) async fn run<TRequest, TResponse>(
+    global_behaviors: &mut GlobalBehaviors,
+    mut next: Next<'_, TRequest, TResponse>,
+    request: TRequest,
+) -> Result<TResponse>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    if global_behaviors.0.is_empty() {
+        return next.run(request).await;
+    }
+    let context = next.context();
+    let mut inner = TypedNext(next);
+    let mut next = AnyNext {
+        request_type_name: std::any::type_name::<TRequest>(),
+        types: (TypeId::of::<TRequest>(), TypeId::of::<TResponse>()),
+        behaviors: &mut global_behaviors.0,
+        inner: &mut inner,
+        context,
+    };
+    match next.run(Box::new(request)).await?.downcast::<TResponse>() {
+        Ok(response) => Ok(*response),
+        Err(_) => Err(Box::new(MediatorError::RequestTypeMismatchError)),
+    }
+}
+
+impl Mediator {
+    /// Registers a global pipeline behavior, which runs for every request sent to the mediator.
+    ///
+    /// Global behaviors run in registration order, the first registered behavior being
+    /// the outermost. They all run before the behaviors registered for the request.
+    pub fn register_behavior<TGlobalPipelineBehavior>(
+        &mut self,
+        behavior: TGlobalPipelineBehavior,
+    ) -> &mut Self
+    where
+        TGlobalPipelineBehavior: GlobalPipelineBehavior + 'static,
+    {
+        self.global_behaviors.0.push(Box::new(behavior));
+        self
+    }
+
+    /// Registers a pipeline behavior for a request.
+    ///
+    /// Behaviors run in registration order, the first registered behavior being the outermost.
+    /// They all run after the global behaviors, so the behaviors of a request are always
+    /// innermost, closest to the request handler.
+    pub fn register_behavior_for<TRequest, TResponse, TPipelineBehavior>(
+        &mut self,
+        behavior: TPipelineBehavior,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TPipelineBehavior: PipelineBehavior<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        match self
+            .behaviors
+            .get_mut::<TRequest, Behaviors<TRequest, TResponse>>()
+            .ok()
+        {
+            Some(behaviors) => behaviors.push(Box::new(behavior)),
+            None => self
+                .behaviors
+                .set::<TRequest, Behaviors<TRequest, TResponse>>(vec![Box::new(behavior)]),
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    pub struct TestRequest(i64);
+
+    impl Request<i64> for TestRequest {}
+
+    #[derive(Debug)]
+    pub struct TestRequestHandler;
+
+    #[async_trait]
+    impl RequestHandler<TestRequest, i64> for TestRequestHandler {
+        async fn handle(&mut self, request: TestRequest) -> Result<i64> {
+            Ok(request.0)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TracingBehavior {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl PipelineBehavior<TestRequest, i64> for TracingBehavior {
+        async fn handle(
+            &mut self,
+            request: TestRequest,
+            mut next: Next<'_, TestRequest, i64>,
+        ) -> Result<i64> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} before", self.name));
+            let response = next.run(request).await;
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} after", self.name));
+            response
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct DoublingBehavior;
+
+    #[async_trait]
+    impl PipelineBehavior<TestRequest, i64> for DoublingBehavior {
+        async fn handle(
+            &mut self,
+            request: TestRequest,
+            mut next: Next<'_, TestRequest, i64>,
+        ) -> Result<i64> {
+            next.run(TestRequest(request.0 * 2)).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_behaviors_run_outside_in() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler)
+            .register_behavior_for(TracingBehavior {
+                name: "outer",
+                log: log.clone(),
+            })
+            .register_behavior_for(TracingBehavior {
+                name: "inner",
+                log: log.clone(),
+            });
+        assert_eq!(m.send(TestRequest(42)).await.unwrap(), 42);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["outer before", "inner before", "inner after", "outer after"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_behavior_can_change_request() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler)
+            .register_behavior_for(DoublingBehavior);
+        assert_eq!(m.send(TestRequest(21)).await.unwrap(), 42);
+    }
+
+    #[derive(Debug)]
+    pub struct GlobalTracingBehavior {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl GlobalPipelineBehavior for GlobalTracingBehavior {
+        async fn handle(
+            &mut self,
+            request: Box<dyn Any + Send>,
+            mut next: AnyNext<'_>,
+        ) -> Result<Box<dyn Any + Send>> {
+            let name = next.request_type_name();
+            self.log.lock().unwrap().push(format!("global {}", name));
+            next.run(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_global_behaviors_run_before_request_behaviors() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler)
+            .register_behavior_for(TracingBehavior {
+                name: "request",
+                log: log.clone(),
+            })
+            .register_behavior(GlobalTracingBehavior { log: log.clone() });
+        assert_eq!(m.send(TestRequest(42)).await.unwrap(), 42);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                format!("global {}", std::any::type_name::<TestRequest>()),
+                String::from("request before"),
+                String::from("request after"),
+            ]
+        );
+    }
+
+    #[derive(Debug)]
+    pub struct ReplacingBehavior;
+
+    #[async_trait]
+    impl GlobalPipelineBehavior for ReplacingBehavior {
+        async fn handle(
+            &mut self,
+            _request: Box<dyn Any + Send>,
+            mut next: AnyNext<'_>,
+        ) -> Result<Box<dyn Any + Send>> {
+            next.run(Box::new("not a request")).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_global_behavior_request_type_mismatch() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler)
+            .register_behavior(ReplacingBehavior);
+        let err = m.send(TestRequest(42)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::RequestTypeMismatchError)
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Mediator, Request, Result};
+
+impl Mediator {
+    /// Send a request to the mediator, blocking the current thread until it is handled.
+    ///
+    /// The handler is driven by a minimal executor on the current thread, not by an async runtime.
+    /// Handlers relying on a runtime, like the tokio timers or IO, panic unless that runtime
+    /// is entered.
+    /// Calling this from within an async runtime blocks one of its threads,
+    /// which may deadlock if the handler waits on work scheduled on that runtime.
+    pub fn blocking_send<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        futures::executor::block_on(self.send(request))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MediatorError;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[test]
+    fn test_mediator_blocking_send() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert_eq!(m.blocking_send(Ping).unwrap(), "pong!");
+    }
+
+    #[test]
+    fn test_mediator_blocking_send_no_handler_registered() {
+        let mut m = Mediator::new();
+        let err = m.blocking_send(Ping).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Ping>().into()
+            ))
+        );
+    }
+}
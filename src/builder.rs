@@ -0,0 +1,125 @@
+use crate::{Mediator, MediatorError, Notification, NotificationHandler, Request, RequestHandler};
+
+/// A builder to construct a mediator.
+///
+/// Registering two handlers for the same request fails the build.
+#[derive(Debug, Default)]
+pub struct MediatorBuilder {
+    mediator: Mediator,
+    error: Option<MediatorError>,
+}
+
+impl MediatorBuilder {
+    /// Creates a new mediator builder.
+    pub fn new() -> Self {
+        MediatorBuilder::default()
+    }
+
+    /// Adds a request handler.
+    pub fn add_handler<TRequest, TRequestHandler, TResponse>(
+        mut self,
+        handler: TRequestHandler,
+    ) -> Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        if self.error.is_none() {
+            if let Err(e) = self.mediator.try_register_handler(handler) {
+                self.error = Some(e);
+            }
+        }
+        self
+    }
+
+    /// Adds a notification handler.
+    pub fn add_notification_handler<TNotification, TNotificationHandler>(
+        mut self,
+        handler: TNotificationHandler,
+    ) -> Self
+    where
+        TNotification: Notification,
+        TNotificationHandler: NotificationHandler<TNotification> + 'static,
+    {
+        self.mediator.register_notification_handler(handler);
+        self
+    }
+
+    /// Builds the mediator.
+    ///
+    /// Returns the first error that occurred while adding the handlers.
+    pub fn build(self) -> Result<Mediator, MediatorError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.mediator),
+        }
+    }
+}
+
+impl Mediator {
+    /// Creates a new mediator builder.
+    pub fn builder() -> MediatorBuilder {
+        MediatorBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct TestRequest;
+
+    impl Request<i64> for TestRequest {}
+
+    #[derive(Debug)]
+    pub struct TestRequestHandler;
+
+    #[async_trait]
+    impl RequestHandler<TestRequest, i64> for TestRequestHandler {
+        async fn handle(&mut self, _request: TestRequest) -> crate::Result<i64> {
+            Ok(42)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TestNotification;
+
+    impl Notification for TestNotification {}
+
+    #[derive(Debug)]
+    pub struct TestNotificationHandler;
+
+    #[async_trait]
+    impl NotificationHandler<TestNotification> for TestNotificationHandler {
+        async fn handle(&mut self, _notification: &TestNotification) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_builder_build() {
+        let mut m = Mediator::builder()
+            .add_handler(TestRequestHandler)
+            .add_notification_handler(TestNotificationHandler)
+            .add_notification_handler(TestNotificationHandler)
+            .build()
+            .unwrap();
+        assert_eq!(m.send(TestRequest).await.unwrap(), 42);
+        assert!(m.publish(TestNotification).await.is_ok());
+    }
+
+    #[test]
+    fn test_mediator_builder_duplicate_handler() {
+        let result = Mediator::builder()
+            .add_handler(TestRequestHandler)
+            .add_handler(TestRequestHandler)
+            .build();
+        assert_eq!(
+            result.err(),
+            Some(MediatorError::HandlerAlreadyRegisteredError)
+        );
+    }
+}
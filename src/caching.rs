@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use crate::{Next, PipelineBehavior, Request, Result};
+
+/// The cacheable trait. Requests implementing it opt in to the caching behavior.
+pub trait Cacheable {
+    /// Returns whether the response to this request may be cached.
+    /// Requests that are not cacheable bypass the cache.
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// A pipeline behavior caching the responses to requests for a given time to live.
+///
+/// A cached response is returned without invoking the handler, and errors are never cached.
+/// Expired responses are removed when they are requested, and at most once per time to live
+/// when a response is cached, so the cache only holds the responses of the last two periods.
+pub struct CachingBehavior<TRequest, TResponse> {
+    ttl: Duration,
+    entries: HashMap<TRequest, (Instant, TResponse)>,
+    pruned_at: Instant,
+}
+
+impl<TRequest, TResponse> CachingBehavior<TRequest, TResponse> {
+    /// Creates a new caching behavior keeping responses for the given time to live.
+    pub fn new(ttl: Duration) -> Self {
+        CachingBehavior {
+            ttl,
+            entries: HashMap::new(),
+            pruned_at: Instant::now(),
+        }
+    }
+}
+
+impl<TRequest, TResponse> CachingBehavior<TRequest, TResponse>
+where
+    TRequest: Hash + Eq,
+{
+    /// Caches the response, removing the expired ones once the time to live has elapsed
+    /// since they were last removed.
+    fn cache(&mut self, request: TRequest, response: TResponse) {
+        let now = Instant::now();
+        if now.duration_since(self.pruned_at) >= self.ttl {
+            let ttl = self.ttl;
+            self.entries
+                .retain(|_, (cached_at, _)| now.duration_since(*cached_at) < ttl);
+            self.pruned_at = now;
+        }
+        self.entries.insert(request, (now, response));
+    }
+}
+
+impl<TRequest, TResponse> std::fmt::Debug for CachingBehavior<TRequest, TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingBehavior")
+            .field("ttl", &self.ttl)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse>
+    for CachingBehavior<TRequest, TResponse>
+where
+    TRequest: Request<TResponse> + Cacheable + Hash + Eq + Clone,
+    TResponse: Clone + Send + Sync + 'static,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        if !request.is_cacheable() {
+            return next.run(request).await;
+        }
+        if let Some((cached_at, response)) = self.entries.get(&request) {
+            if cached_at.elapsed() < self.ttl {
+                return Ok(response.clone());
+            }
+            self.entries.remove(&request);
+        }
+        let response = next.run(request.clone()).await?;
+        self.cache(request, response.clone());
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Square(u64);
+
+    impl Request<u64> for Square {}
+
+    impl Cacheable for Square {
+        fn is_cacheable(&self) -> bool {
+            self.0 != 0
+        }
+    }
+
+    fn mediator(ttl: Duration, calls: Arc<AtomicUsize>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |request: Square| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(request.0 * request.0) }
+        })
+        .register_behavior_for::<Square, u64, _>(CachingBehavior::new(ttl));
+        m
+    }
+
+    #[tokio::test]
+    async fn test_caching_behavior_hit() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut m = mediator(Duration::from_secs(3600), calls.clone());
+        assert_eq!(m.send(Square(3)).await.unwrap(), 9);
+        assert_eq!(m.send(Square(3)).await.unwrap(), 9);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_caching_behavior_miss() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut m = mediator(Duration::from_secs(3600), calls.clone());
+        assert_eq!(m.send(Square(3)).await.unwrap(), 9);
+        assert_eq!(m.send(Square(4)).await.unwrap(), 16);
+        assert_eq!(m.send(Square(0)).await.unwrap(), 0);
+        assert_eq!(m.send(Square(0)).await.unwrap(), 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_caching_behavior_expiry() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut m = mediator(Duration::ZERO, calls.clone());
+        assert_eq!(m.send(Square(3)).await.unwrap(), 9);
+        assert_eq!(m.send(Square(3)).await.unwrap(), 9);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_caching_behavior_prunes_expired_responses() {
+        let mut behavior = CachingBehavior::new(Duration::from_millis(10));
+        behavior.cache(Square(1), 1);
+        behavior.cache(Square(2), 4);
+        assert_eq!(behavior.entries.len(), 2);
+        std::thread::sleep(Duration::from_millis(20));
+        behavior.cache(Square(3), 9);
+        assert_eq!(behavior.entries.len(), 1);
+        assert!(behavior.entries.contains_key(&Square(3)));
+    }
+}
@@ -0,0 +1,71 @@
+use futures::future::{select, Either};
+use tokio_util::sync::CancellationToken;
+
+use crate::{Mediator, MediatorError, Request, Result};
+
+impl Mediator {
+    /// Send a request to the mediator, failing with `MediatorError::CancelledError`
+    /// if the token is cancelled before the handler completes.
+    ///
+    /// The handler is abandoned at its current await point once the token is cancelled.
+    pub async fn send_with_cancellation<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+        token: CancellationToken,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let cancelled = Box::pin(token.cancelled());
+        let send = Box::pin(self.send(request));
+        match select(cancelled, send).await {
+            Either::Left(_) => Err(Box::new(MediatorError::CancelledError)),
+            Either::Right((response, _)) => response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Wait(bool);
+
+    impl Request<()> for Wait {}
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Wait| async move {
+            if request.0 {
+                futures::future::pending::<()>().await;
+            }
+            Ok(())
+        });
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_cancellation() {
+        let mut m = mediator();
+        let token = CancellationToken::new();
+        assert!(m.send_with_cancellation(Wait(false), token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_cancellation_cancelled() {
+        let mut m = mediator();
+        let token = CancellationToken::new();
+        let child = token.child_token();
+        tokio::spawn(async move { token.cancel() });
+        let err = m
+            .send_with_cancellation(Wait(true), child)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::CancelledError)
+        );
+    }
+}
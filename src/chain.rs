@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{info::HandlerInfo, Mediator, MediatorError, Request, Result};
+
+/// The chainable handler trait. Unlike the request handler, a chainable handler receives
+/// the mediator, so it can send follow-up requests to other handlers.
+#[async_trait]
+pub trait ChainableHandler<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    async fn handle(&mut self, request: TRequest, mediator: &mut Mediator) -> Result<TResponse>;
+}
+
+/// The slot of a chainable handler, which is empty while the handler handles a request.
+///
+/// The slot is shared so the handler can be put back when the request is dropped,
+/// while the handler still borrows the mediator.
+pub(crate) type ChainableHandlerSlot<TRequest, TResponse> =
+    Arc<Mutex<Option<Box<dyn ChainableHandler<TRequest, TResponse>>>>>;
+
+/// Puts the handler back in its slot once the request is handled or dropped.
+struct SlotGuard<TRequest, TResponse> {
+    slot: ChainableHandlerSlot<TRequest, TResponse>,
+    handler: Option<Box<dyn ChainableHandler<TRequest, TResponse>>>,
+}
+
+impl<TRequest, TResponse> Drop for SlotGuard<TRequest, TResponse> {
+    fn drop(&mut self) {
+        *self.slot.lock().unwrap() = self.handler.take();
+    }
+}
+
+impl Mediator {
+    /// Registers a chainable handler, replacing any request handler of the request.
+    ///
+    /// The handler is taken out of the mediator while it handles a request, so a chainable
+    /// handler sending its own request type fails with `MediatorError::RecursiveRequestError`.
+    /// Its requests are dispatched without the processors and the pipeline behaviors,
+    /// which cannot be borrowed while the handler holds the mediator; the follow-up requests
+    /// go through the whole pipeline as usual.
+    pub fn register_chainable_handler<TRequest, TChainableHandler, TResponse>(
+        &mut self,
+        handler: TChainableHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TChainableHandler: ChainableHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.unregister_handler::<TRequest, TResponse>();
+        self.chainable_handlers
+            .set::<TRequest, ChainableHandlerSlot<TRequest, TResponse>>(Arc::new(Mutex::new(
+                Some(Box::new(handler)),
+            )));
+        self.handler_infos.insert(
+            std::any::TypeId::of::<TRequest>(),
+            HandlerInfo::of::<TRequest, TResponse>(),
+        );
+        self
+    }
+}
+
+/// Sends the request to its chainable handler, lending it the mediator.
+pub(crate) async fn dispatch<TRequest, TResponse>(
+    mediator: &mut Mediator,
+    request: TRequest,
+    elapsed: Option<&mut Duration>,
+) -> Result<TResponse>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    let slot = match mediator
+        .chainable_handlers
+        .get_mut::<TRequest, ChainableHandlerSlot<TRequest, TResponse>>()
+    {
+        Ok(slot) => slot.clone(),
+        Err(e) => return Err(Box::new(e)),
+    };
+    let handler = slot.lock().unwrap().take();
+    let mut guard = SlotGuard { slot, handler };
+    let Some(handler) = guard.handler.as_mut() else {
+        return Err(Box::new(MediatorError::RecursiveRequestError));
+    };
+    let start = Instant::now();
+    let response = handler.handle(request, mediator).await;
+    if let Some(elapsed) = elapsed {
+        *elapsed += start.elapsed();
+    }
+    response
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct GetPrice(u64);
+
+    impl Request<u64> for GetPrice {}
+
+    #[derive(Debug)]
+    pub struct GetTotal(Vec<u64>);
+
+    impl Request<u64> for GetTotal {}
+
+    #[derive(Debug)]
+    pub struct GetTotalHandler;
+
+    #[async_trait]
+    impl ChainableHandler<GetTotal, u64> for GetTotalHandler {
+        async fn handle(&mut self, request: GetTotal, mediator: &mut Mediator) -> Result<u64> {
+            let mut total = 0;
+            for id in request.0 {
+                total += mediator.send(GetPrice(id)).await?;
+            }
+            Ok(total)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct RecursiveHandler;
+
+    #[async_trait]
+    impl ChainableHandler<GetTotal, u64> for RecursiveHandler {
+        async fn handle(&mut self, request: GetTotal, mediator: &mut Mediator) -> Result<u64> {
+            mediator.send(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_chainable_handler() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: GetPrice| async move { Ok(request.0 * 10) })
+            .register_chainable_handler(GetTotalHandler);
+        assert_eq!(m.send(GetTotal(vec![1, 2, 3])).await.unwrap(), 60);
+        assert_eq!(m.send(GetTotal(vec![4])).await.unwrap(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_chainable_handler_recursive_request() {
+        let mut m = Mediator::new();
+        m.register_chainable_handler(RecursiveHandler);
+        let err = m.send(GetTotal(vec![])).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::RecursiveRequestError)
+        );
+        assert!(m.send(GetTotal(vec![])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_chainable_handler_dropped_request() {
+        #[derive(Debug)]
+        pub struct SlowTotalHandler;
+
+        #[async_trait]
+        impl ChainableHandler<GetTotal, u64> for SlowTotalHandler {
+            async fn handle(&mut self, request: GetTotal, _mediator: &mut Mediator) -> Result<u64> {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(request.0.iter().sum())
+            }
+        }
+
+        let mut m = Mediator::new();
+        m.register_chainable_handler(SlowTotalHandler);
+        let dropped =
+            tokio::time::timeout(Duration::from_millis(5), m.send(GetTotal(vec![1]))).await;
+        assert!(dropped.is_err());
+        assert_eq!(m.send(GetTotal(vec![1, 2])).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+
+use crate::{MediatorError, Next, PipelineBehavior, Request, Result};
+
+#[derive(Debug, Clone, Copy)]
+enum CircuitState {
+    /// Requests are handled, counting the consecutive failures.
+    Closed { failures: usize },
+    /// Requests fail without being handled.
+    Open { since: Instant },
+    /// Trial requests are handled, counting the consecutive successes.
+    HalfOpen { successes: usize },
+}
+
+/// A pipeline behavior that stops handling requests once the handler keeps failing,
+/// e.g. to stop hammering a failing dependency.
+///
+/// After `failure_threshold` consecutive failures, the circuit opens: requests fail with
+/// `MediatorError::CircuitOpenError` without being handled, for the open duration. The circuit
+/// is then half-open: requests are handled again as trials, and the circuit closes after
+/// enough consecutive successful trials, or opens again on the first failed one.
+#[derive(Debug)]
+pub struct CircuitBreakerBehavior {
+    failure_threshold: usize,
+    open_duration: Duration,
+    trial_successes: usize,
+    state: CircuitState,
+}
+
+impl CircuitBreakerBehavior {
+    /// Creates a behavior opening the circuit after `failure_threshold` consecutive failures,
+    /// for the given duration.
+    pub fn new(failure_threshold: usize, open_duration: Duration) -> Self {
+        CircuitBreakerBehavior {
+            failure_threshold,
+            open_duration,
+            trial_successes: 1,
+            state: CircuitState::Closed { failures: 0 },
+        }
+    }
+
+    /// Sets the number of consecutive successful trials closing a half-open circuit,
+    /// which is 1 by default.
+    pub fn with_trial_successes(mut self, trial_successes: usize) -> Self {
+        self.trial_successes = trial_successes;
+        self
+    }
+
+    fn on_success(&mut self) {
+        self.state = match self.state {
+            CircuitState::HalfOpen { successes } if successes + 1 < self.trial_successes => {
+                CircuitState::HalfOpen {
+                    successes: successes + 1,
+                }
+            }
+            _ => CircuitState::Closed { failures: 0 },
+        }
+    }
+
+    fn on_failure(&mut self) {
+        self.state = match self.state {
+            CircuitState::Closed { failures } if failures + 1 < self.failure_threshold => {
+                CircuitState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            _ => CircuitState::Open {
+                since: Instant::now(),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for CircuitBreakerBehavior
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        if let CircuitState::Open { since } = self.state {
+            if since.elapsed() < self.open_duration {
+                return Err(Box::new(MediatorError::CircuitOpenError));
+            }
+            self.state = CircuitState::HalfOpen { successes: 0 };
+        }
+        let result = next.run(request).await;
+        match result {
+            Ok(_) => self.on_success(),
+            Err(_) => self.on_failure(),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug)]
+    pub struct Call;
+
+    impl Request<()> for Call {}
+
+    struct Dependency {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    fn mediator(behavior: CircuitBreakerBehavior) -> (Mediator, Arc<Dependency>) {
+        let dependency = Arc::new(Dependency {
+            failing: AtomicBool::new(true),
+            calls: AtomicUsize::new(0),
+        });
+        let handler_dependency = dependency.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Call| {
+            handler_dependency.calls.fetch_add(1, Ordering::SeqCst);
+            let failing = handler_dependency.failing.load(Ordering::SeqCst);
+            async move {
+                if failing {
+                    return Err("unavailable".into());
+                }
+                Ok(())
+            }
+        })
+        .register_behavior_for::<Call, (), _>(behavior);
+        (m, dependency)
+    }
+
+    fn is_circuit_open(result: Result<()>) -> bool {
+        result.unwrap_err().downcast_ref::<MediatorError>()
+            == Some(&MediatorError::CircuitOpenError)
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_behavior_opens() {
+        let (mut m, dependency) =
+            mediator(CircuitBreakerBehavior::new(2, Duration::from_millis(20)));
+        assert!(!is_circuit_open(m.send(Call).await));
+        assert!(!is_circuit_open(m.send(Call).await));
+        assert!(is_circuit_open(m.send(Call).await));
+        assert_eq!(dependency.calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!is_circuit_open(m.send(Call).await));
+        assert!(is_circuit_open(m.send(Call).await));
+        assert_eq!(dependency.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_behavior_closes_after_trials() {
+        let behavior =
+            CircuitBreakerBehavior::new(1, Duration::from_millis(20)).with_trial_successes(2);
+        let (mut m, dependency) = mediator(behavior);
+        assert!(!is_circuit_open(m.send(Call).await));
+        assert!(is_circuit_open(m.send(Call).await));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        dependency.failing.store(false, Ordering::SeqCst);
+        assert!(m.send(Call).await.is_ok());
+        assert!(m.send(Call).await.is_ok());
+        dependency.failing.store(true, Ordering::SeqCst);
+        assert!(!is_circuit_open(m.send(Call).await));
+        assert_eq!(dependency.calls.load(Ordering::SeqCst), 4);
+    }
+}
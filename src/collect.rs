@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+
+use crate::{Mediator, Notification, Result};
+
+/// The collecting notification handler trait. Like a notification handler, any number of
+/// handlers can be registered for a notification, but each one returns an acknowledgment,
+/// e.g. a vote, collected by `Mediator::publish_collect`.
+#[async_trait]
+pub trait CollectingNotificationHandler<TNotification, TAck>: Send + Sync
+where
+    TNotification: Notification,
+{
+    /// The method that handles the notification and returns the acknowledgment.
+    async fn handle(&mut self, notification: &TNotification) -> Result<TAck>;
+}
+
+/// The collecting handlers of a notification, in registration order.
+type CollectingHandlers<TNotification, TAck> =
+    Vec<Box<dyn CollectingNotificationHandler<TNotification, TAck>>>;
+
+impl Mediator {
+    /// Registers a collecting handler, returning acknowledgments of the given type.
+    ///
+    /// Collecting handlers are separate from the notification handlers: they are not invoked
+    /// by `publish`, and `publish_collect` does not invoke the notification handlers.
+    pub fn register_collecting_handler<TNotification, THandler, TAck>(
+        &mut self,
+        handler: THandler,
+    ) -> &mut Self
+    where
+        TNotification: Notification,
+        THandler: CollectingNotificationHandler<TNotification, TAck> + 'static,
+        TAck: 'static,
+    {
+        match self
+            .collecting_handlers
+            .get_mut::<(TNotification, TAck), CollectingHandlers<TNotification, TAck>>()
+        {
+            Ok(handlers) => handlers.push(Box::new(handler)),
+            Err(_) => self
+                .collecting_handlers
+                .set::<(TNotification, TAck), CollectingHandlers<TNotification, TAck>>(vec![
+                    Box::new(handler),
+                ]),
+        }
+        self
+    }
+
+    /// Publish a notification to every collecting handler returning acknowledgments of the
+    /// given type, and collect one result per handler, in registration order.
+    ///
+    /// The handlers are invoked one after the other, and an error does not stop the publishing.
+    /// Publishing a notification without any registered collecting handler returns no result.
+    pub async fn publish_collect<TNotification, TAck>(
+        &mut self,
+        notification: TNotification,
+    ) -> Vec<Result<TAck>>
+    where
+        TNotification: Notification,
+        TAck: 'static,
+    {
+        let handlers = match self
+            .collecting_handlers
+            .get_mut::<(TNotification, TAck), CollectingHandlers<TNotification, TAck>>()
+        {
+            Ok(handlers) => handlers,
+            Err(_) => return Vec::new(),
+        };
+        let mut acks = Vec::with_capacity(handlers.len());
+        for handler in handlers.iter_mut() {
+            acks.push(handler.handle(&notification).await);
+        }
+        acks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Proposal(u32);
+
+    impl Notification for Proposal {}
+
+    #[derive(Debug)]
+    pub struct Voter {
+        max: u32,
+    }
+
+    #[async_trait]
+    impl CollectingNotificationHandler<Proposal, bool> for Voter {
+        async fn handle(&mut self, notification: &Proposal) -> Result<bool> {
+            match self.max {
+                0 => Err("abstained".into()),
+                max => Ok(notification.0 <= max),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_collect() {
+        let mut m = Mediator::new();
+        m.register_collecting_handler(Voter { max: 10 })
+            .register_collecting_handler(Voter { max: 0 })
+            .register_collecting_handler(Voter { max: 3 });
+        let acks = m.publish_collect::<_, bool>(Proposal(5)).await;
+        assert_eq!(acks.len(), 3);
+        assert!(acks[0].as_ref().unwrap());
+        assert_eq!(acks[1].as_ref().unwrap_err().to_string(), "abstained");
+        assert!(!acks[2].as_ref().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_collect_without_handlers() {
+        let mut m = Mediator::new();
+        m.register_collecting_handler(Voter { max: 10 });
+        assert!(m.publish_collect::<_, String>(Proposal(5)).await.is_empty());
+    }
+}
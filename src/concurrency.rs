@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::{MediatorError, Next, PipelineBehavior, Request, Result};
+
+/// A pipeline behavior limiting how many requests are handled concurrently,
+/// e.g. to protect a downstream dependency.
+///
+/// The limit applies across the mediators sharing clones of the behavior, see the
+/// concurrency section of [`Mediator::send`](crate::Mediator::send).
+/// Once the limit is reached, requests wait for a permit, or fail with
+/// `MediatorError::ConcurrencyLimitExceededError` if the behavior fails fast.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitBehavior {
+    semaphore: Arc<Semaphore>,
+    fail_fast: bool,
+}
+
+impl ConcurrencyLimitBehavior {
+    /// Creates a behavior handling at most `permits` requests concurrently.
+    pub fn new(permits: usize) -> Self {
+        ConcurrencyLimitBehavior {
+            semaphore: Arc::new(Semaphore::new(permits)),
+            fail_fast: false,
+        }
+    }
+
+    /// Fails the requests exceeding the limit instead of making them wait.
+    pub fn fail_fast(mut self) -> Self {
+        self.fail_fast = true;
+        self
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for ConcurrencyLimitBehavior
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let _permit = if self.fail_fast {
+            self.semaphore
+                .try_acquire()
+                .map_err(|_| MediatorError::ConcurrencyLimitExceededError)?
+        } else {
+            self.semaphore
+                .acquire()
+                .await
+                .expect("the semaphore is never closed")
+        };
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[derive(Debug)]
+    pub struct Query;
+
+    impl Request<usize> for Query {}
+
+    /// Registers a handler returning the number of requests it handles concurrently.
+    fn mediator(behavior: &ConcurrencyLimitBehavior, running: Arc<AtomicUsize>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Query| {
+            let running = running.clone();
+            async move {
+                let concurrent = running.fetch_add(1, Ordering::SeqCst) + 1;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(concurrent)
+            }
+        })
+        .register_behavior_for::<Query, usize, _>(behavior.clone());
+        m
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_behavior_waits() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let behavior = ConcurrencyLimitBehavior::new(1);
+        let mut m1 = mediator(&behavior, running.clone());
+        let mut m2 = mediator(&behavior, running.clone());
+        let (r1, r2) = tokio::join!(m1.send(Query), m2.send(Query));
+        assert_eq!(r1.unwrap(), 1);
+        assert_eq!(r2.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_behavior_fail_fast() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let behavior = ConcurrencyLimitBehavior::new(1).fail_fast();
+        let mut m1 = mediator(&behavior, running.clone());
+        let mut m2 = mediator(&behavior, running.clone());
+        let (r1, r2) = tokio::join!(m1.send(Query), m2.send(Query));
+        assert_eq!(r1.unwrap(), 1);
+        assert_eq!(
+            r2.unwrap_err().downcast_ref::<MediatorError>(),
+            Some(&MediatorError::ConcurrencyLimitExceededError)
+        );
+    }
+}
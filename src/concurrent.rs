@@ -0,0 +1,140 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Mediator, Request, Result};
+
+/// A mediator that can be cloned and shared across tasks, behind a read-write lock.
+///
+/// Unlike the `SharedMediator`, whose requests are handled one at a time, requests are
+/// dispatched to the read handlers, which only borrow themselves: the lock is only read
+/// to look up the handler, so requests sent from different tasks are handled concurrently,
+/// and registering a handler takes the write lock.
+///
+/// Handlers needing `&mut self`, like a `RequestHandler`, are registered as read handlers
+/// by wrapping them in a `futures::lock::Mutex`, so each of them handles one request at a time
+/// while the others keep handling requests. The pipeline behaviors and processors are not run,
+/// as they require exclusive access to the mediator.
+#[derive(Clone, Debug, Default)]
+pub struct ConcurrentMediator {
+    mediator: Arc<RwLock<Mediator>>,
+}
+
+impl ConcurrentMediator {
+    /// Creates a new concurrent mediator from a mediator.
+    pub fn new(mediator: Mediator) -> Self {
+        ConcurrentMediator {
+            mediator: Arc::new(RwLock::new(mediator)),
+        }
+    }
+
+    /// Locks the underlying mediator for reading.
+    ///
+    /// Panics if a thread panicked while holding the write lock.
+    pub fn read(&self) -> RwLockReadGuard<'_, Mediator> {
+        self.mediator.read().unwrap()
+    }
+
+    /// Locks the underlying mediator for writing, e.g. to register additional handlers.
+    ///
+    /// The lock is not async, so the guard must not be held across an await point.
+    /// Panics if a thread panicked while holding the write lock.
+    pub fn write(&self) -> RwLockWriteGuard<'_, Mediator> {
+        self.mediator.write().unwrap()
+    }
+
+    /// Send a request to its read handler.
+    ///
+    /// The mediator is only locked to look up the handler, not while the request is handled.
+    pub async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = self.read().read_handler()?;
+        handler.handle(request).await
+    }
+}
+
+impl From<Mediator> for ConcurrentMediator {
+    fn from(mediator: Mediator) -> Self {
+        ConcurrentMediator::new(mediator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MediatorError, ReadHandler, RequestHandler};
+    use async_trait::async_trait;
+    use futures::lock::Mutex;
+
+    #[derive(Debug)]
+    pub struct CountRequest;
+
+    impl Request<usize> for CountRequest {}
+
+    #[derive(Debug, Default)]
+    pub struct CountRequestHandler(usize);
+
+    #[async_trait]
+    impl RequestHandler<CountRequest, usize> for CountRequestHandler {
+        async fn handle(&mut self, _request: CountRequest) -> Result<usize> {
+            self.0 += 1;
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_mediator_query_from_tasks() {
+        let concurrent = ConcurrentMediator::default();
+        assert_eq!(
+            concurrent
+                .query(CountRequest)
+                .await
+                .unwrap_err()
+                .downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<CountRequest>().into()
+            ))
+        );
+        concurrent
+            .write()
+            .register_read_handler(Mutex::new(CountRequestHandler::default()));
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let concurrent = concurrent.clone();
+                tokio::spawn(async move { concurrent.query(CountRequest).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(concurrent.query(CountRequest).await.unwrap(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_mediator_query_does_not_hold_the_lock() {
+        #[derive(Debug)]
+        pub struct Nested;
+
+        impl Request<usize> for Nested {}
+
+        #[derive(Debug)]
+        pub struct NestedHandler(ConcurrentMediator);
+
+        #[async_trait]
+        impl ReadHandler<Nested, usize> for NestedHandler {
+            async fn handle(&self, _request: Nested) -> Result<usize> {
+                self.0.query(CountRequest).await
+            }
+        }
+
+        let concurrent = ConcurrentMediator::from(Mediator::new());
+        concurrent
+            .write()
+            .register_read_handler(Mutex::new(CountRequestHandler::default()))
+            .register_read_handler(NestedHandler(concurrent.clone()));
+        assert_eq!(concurrent.query(Nested).await.unwrap(), 1);
+    }
+}
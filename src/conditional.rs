@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+use crate::{Mediator, MediatorError, Request, RequestContext, RequestHandler, Result};
+
+type Predicate<TRequest> = Box<dyn Fn(&TRequest) -> bool + Send + Sync>;
+
+type ConditionalHandler<TRequest, TResponse> = (
+    Predicate<TRequest>,
+    Box<dyn RequestHandler<TRequest, TResponse>>,
+);
+
+/// The request handlers registered with a predicate for a request type, in registration order.
+pub(crate) struct ConditionalHandlers<TRequest, TResponse>(
+    Vec<ConditionalHandler<TRequest, TResponse>>,
+);
+
+pub(crate) type SharedConditionalHandlers<TRequest, TResponse> =
+    Arc<Mutex<ConditionalHandlers<TRequest, TResponse>>>;
+
+impl<TRequest, TResponse> ConditionalHandlers<TRequest, TResponse> {
+    fn select(
+        &mut self,
+        request: &TRequest,
+    ) -> Result<&mut Box<dyn RequestHandler<TRequest, TResponse>>> {
+        match self.0.iter_mut().find(|(predicate, _)| predicate(request)) {
+            Some((_, handler)) => Ok(handler),
+            None => Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TRequest>().into(),
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for ConditionalHandlers<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        self.select(&request)?.handle(request).await
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.select(&request)?
+            .handle_with_context(request, context)
+            .await
+    }
+
+    async fn on_shutdown(&mut self) {
+        for (_, handler) in self.0.iter_mut() {
+            handler.on_shutdown().await;
+        }
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler for the requests matching the predicate,
+    /// e.g. to route requests by tier.
+    ///
+    /// Several handlers can be registered with a predicate for the same request type:
+    /// a request is handled by the first one, in registration order, whose predicate matches.
+    /// If none matches, the request fails with `MediatorError::HandlerNotRegisteredError`.
+    /// Registering a handler with `register_handler` replaces every conditional handler.
+    pub fn register_handler_if<TRequest, TRequestHandler, TResponse, F>(
+        &mut self,
+        predicate: F,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+        F: Fn(&TRequest) -> bool + Send + Sync + 'static,
+    {
+        let handlers = match self
+            .conditional_handlers
+            .remove::<TRequest, SharedConditionalHandlers<TRequest, TResponse>>()
+        {
+            Some(handlers) => handlers,
+            None => Arc::new(Mutex::new(ConditionalHandlers(Vec::new()))),
+        };
+        handlers
+            .try_lock()
+            .expect("the handlers are only locked while handling a request")
+            .0
+            .push((Box::new(predicate), Box::new(handler)));
+        self.register_handler(handlers.clone());
+        self.conditional_handlers
+            .set::<TRequest, SharedConditionalHandlers<TRequest, TResponse>>(handlers);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Download {
+        premium: bool,
+        size: u64,
+    }
+
+    impl Request<&'static str> for Download {}
+
+    #[derive(Debug)]
+    pub struct LaneHandler(&'static str);
+
+    #[async_trait]
+    impl RequestHandler<Download, &'static str> for LaneHandler {
+        async fn handle(&mut self, _request: Download) -> Result<&'static str> {
+            Ok(self.0)
+        }
+    }
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_if(|request: &Download| request.premium, LaneHandler("fast"))
+            .register_handler_if(
+                |request: &Download| request.size <= 100,
+                LaneHandler("slow"),
+            );
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_if() {
+        let mut m = mediator();
+        let download = |premium, size| Download { premium, size };
+        assert_eq!(m.send(download(true, 10)).await.unwrap(), "fast");
+        assert_eq!(m.send(download(true, 1000)).await.unwrap(), "fast");
+        assert_eq!(m.send(download(false, 10)).await.unwrap(), "slow");
+        let err = m.send(download(false, 1000)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Download>().into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_replaces_conditional_handlers() {
+        let mut m = mediator();
+        m.register_handler(LaneHandler("default"))
+            .register_handler_if(|request: &Download| request.size > 100, LaneHandler("bulk"));
+        let download = |premium, size| Download { premium, size };
+        assert_eq!(m.send(download(true, 1000)).await.unwrap(), "bulk");
+        assert!(m.send(download(true, 10)).await.is_err());
+    }
+}
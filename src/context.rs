@@ -0,0 +1,200 @@
+use std::{
+    any::Any,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{AnyMap, ServiceProvider};
+
+/// A context flowing with a request through the pipeline behaviors and into the handler,
+/// e.g. to carry a correlation id, a tenant or a deadline.
+///
+/// The context holds at most one value of each type, keyed by the type itself,
+/// e.g. `context.get::<TenantId>()`, so a key cannot be misspelled or bound to a value
+/// of another type. Define a newtype to hold several values of the same type.
+#[derive(Debug)]
+pub struct RequestContext {
+    values: AnyMap,
+    services: OnceLock<Arc<ServiceProvider>>,
+    #[cfg(feature = "uuid")]
+    request_id: OnceLock<crate::RequestId>,
+    #[cfg(feature = "timeout")]
+    timeout: OnceLock<std::time::Duration>,
+}
+
+impl RequestContext {
+    /// Creates a new empty context.
+    pub fn new() -> Self {
+        RequestContext {
+            values: AnyMap::new(),
+            services: OnceLock::new(),
+            #[cfg(feature = "uuid")]
+            request_id: OnceLock::new(),
+            #[cfg(feature = "timeout")]
+            timeout: OnceLock::new(),
+        }
+    }
+
+    /// Adds a value to the context, replacing the value of the same type.
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Inserts a value in the context, replacing the value of the same type.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.set::<T, T>(value);
+    }
+
+    /// Returns the value of the given type.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values.get::<T, T>()
+    }
+
+    /// Sets the services of the request, which are used instead of those of the mediator.
+    pub fn with_services(mut self, services: ServiceProvider) -> Self {
+        self.services = OnceLock::from(Arc::new(services));
+        self
+    }
+
+    /// Returns the service of the given type,
+    /// registered on the mediator with `Mediator::register_service`.
+    pub fn resolve<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.services.get()?.resolve::<T>()
+    }
+
+    pub(crate) fn set_services(&self, services: Arc<ServiceProvider>) {
+        let _ = self.services.set(services);
+    }
+
+    /// Sets the id of the request, which the `RequestIdBehavior` keeps instead of generating one.
+    #[cfg(feature = "uuid")]
+    pub fn with_request_id(mut self, id: crate::RequestId) -> Self {
+        self.request_id = OnceLock::from(id);
+        self
+    }
+
+    /// Returns the id of the request, set by the caller or by the `RequestIdBehavior`.
+    #[cfg(feature = "uuid")]
+    pub fn request_id(&self) -> Option<crate::RequestId> {
+        self.request_id.get().copied()
+    }
+
+    /// Sets the timeout of the request, overriding the default timeout of its handler.
+    #[cfg(feature = "timeout")]
+    pub(crate) fn set_timeout(&self, duration: std::time::Duration) {
+        let _ = self.timeout.set(duration);
+    }
+
+    #[cfg(feature = "timeout")]
+    pub(crate) fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout.get().copied()
+    }
+
+    #[cfg(feature = "uuid")]
+    pub(crate) fn request_id_or_random(&self) -> crate::RequestId {
+        *self.request_id.get_or_init(crate::RequestId::random)
+    }
+}
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        RequestContext::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Mediator, Next, PipelineBehavior, Request, RequestHandler, Result};
+    use async_trait::async_trait;
+
+    #[derive(Debug, PartialEq)]
+    pub struct CorrelationId(&'static str);
+
+    #[derive(Debug)]
+    pub struct Whoami;
+
+    impl Request<String> for Whoami {}
+
+    #[derive(Debug)]
+    pub struct WhoamiHandler;
+
+    #[async_trait]
+    impl RequestHandler<Whoami, String> for WhoamiHandler {
+        async fn handle(&mut self, _request: Whoami) -> Result<String> {
+            Ok(String::from("anonymous"))
+        }
+
+        async fn handle_with_context(
+            &mut self,
+            request: Whoami,
+            context: &RequestContext,
+        ) -> Result<String> {
+            match context.get::<CorrelationId>() {
+                Some(id) => Ok(String::from(id.0)),
+                None => self.handle(request).await,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct RequireCorrelationId;
+
+    #[async_trait]
+    impl PipelineBehavior<Whoami, String> for RequireCorrelationId {
+        async fn handle(
+            &mut self,
+            request: Whoami,
+            mut next: Next<'_, Whoami, String>,
+        ) -> Result<String> {
+            if next.context().get::<CorrelationId>().is_none() {
+                return Err("missing correlation id".into());
+            }
+            next.run(request).await
+        }
+    }
+
+    #[test]
+    fn test_request_context_get() {
+        let context = RequestContext::new().with(CorrelationId("abc")).with(42u32);
+        assert_eq!(context.get::<CorrelationId>(), Some(&CorrelationId("abc")));
+        assert_eq!(context.get::<u32>(), Some(&42));
+        assert_eq!(context.get::<u64>(), None);
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct TenantId(u32);
+
+    #[test]
+    fn test_request_context_insert() {
+        let mut context = RequestContext::new();
+        context.insert(TenantId(1));
+        context.insert(CorrelationId("abc"));
+        context.insert(TenantId(2));
+        assert_eq!(context.get::<TenantId>(), Some(&TenantId(2)));
+        assert_eq!(context.get::<CorrelationId>(), Some(&CorrelationId("abc")));
+        assert_eq!(context.get::<u32>(), None);
+        let context = context.with(CorrelationId("def"));
+        assert_eq!(context.get::<CorrelationId>(), Some(&CorrelationId("def")));
+        assert_eq!(context.get::<TenantId>(), Some(&TenantId(2)));
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_context() {
+        let mut m = Mediator::new();
+        m.register_handler(WhoamiHandler);
+        assert_eq!(m.send(Whoami).await.unwrap(), "anonymous");
+        let context = RequestContext::new().with(CorrelationId("abc"));
+        assert_eq!(m.send_with_context(Whoami, context).await.unwrap(), "abc");
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_context_through_behaviors() {
+        let mut m = Mediator::new();
+        m.register_handler(WhoamiHandler)
+            .register_behavior_for(RequireCorrelationId);
+        assert!(m.send(Whoami).await.is_err());
+        let context = RequestContext::new().with(CorrelationId("abc"));
+        assert_eq!(m.send_with_context(Whoami, context).await.unwrap(), "abc");
+    }
+}
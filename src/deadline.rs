@@ -0,0 +1,261 @@
+use pin_project_lite::pin_project;
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "timeout")]
+use crate::{AnyNext, GlobalPipelineBehavior, MediatorError, Result};
+#[cfg(feature = "timeout")]
+use async_trait::async_trait;
+#[cfg(feature = "timeout")]
+use std::any::Any;
+
+/// The instant by which a request has to be handled, carried by the `RequestContext`.
+///
+/// A deadline is absolute, so the time remaining shrinks as the request goes through the
+/// pipeline. The requests sent by a handler inherit the deadline of the request it handles,
+/// unless their context holds one. With the `timeout` feature, `send` fails a request with
+/// `MediatorError::DeadlineExceededError` once its deadline is exceeded, and its timeout,
+/// if any, is shortened to the time remaining.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// Creates a deadline at the given instant.
+    pub fn at(instant: Instant) -> Self {
+        Deadline(instant)
+    }
+
+    /// Creates a deadline the given duration from now.
+    pub fn after(duration: Duration) -> Self {
+        Deadline(Instant::now() + duration)
+    }
+
+    /// Returns the instant of the deadline.
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Returns the time remaining until the deadline, which is zero once it is exceeded.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns `true` if the deadline is exceeded.
+    pub fn is_exceeded(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+thread_local! {
+    /// The deadline of the request whose future is being polled on this thread.
+    static CURRENT: Cell<Option<Deadline>> = const { Cell::new(None) };
+}
+
+/// Returns the deadline of the request being handled, inherited by the requests it sends.
+pub(crate) fn current() -> Option<Deadline> {
+    CURRENT.with(Cell::get)
+}
+
+/// Restores the deadline of the enclosing request once the future is polled, even on panic.
+struct Restore(Option<Deadline>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+pin_project! {
+    /// Makes the deadline of a request the current one while its future is polled.
+    pub(crate) struct WithDeadline<F> {
+        deadline: Option<Deadline>,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F> WithDeadline<F> {
+    pub(crate) fn new(deadline: Option<Deadline>, future: F) -> Self {
+        WithDeadline { deadline, future }
+    }
+}
+
+impl<F: Future> Future for WithDeadline<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _restore = Restore(CURRENT.with(|current| current.replace(*this.deadline)));
+        this.future.poll(cx)
+    }
+}
+
+/// A global pipeline behavior failing requests with `MediatorError::DeadlineExceededError`
+/// when they are not handled before the `Deadline` of their context.
+///
+/// Requests sent without a deadline are not limited.
+/// Waiting for the deadline relies on the runtime timer.
+///
+/// `send` already enforces the deadline around the whole pipeline; the behavior enforces it
+/// again from its position among the global behaviors, e.g. so the behaviors registered
+/// before it observe the `MediatorError::DeadlineExceededError`.
+#[cfg(feature = "timeout")]
+#[derive(Debug, Default)]
+pub struct DeadlineBehavior;
+
+#[cfg(feature = "timeout")]
+#[async_trait]
+impl GlobalPipelineBehavior for DeadlineBehavior {
+    async fn handle(
+        &mut self,
+        request: Box<dyn Any + Send>,
+        mut next: AnyNext<'_>,
+    ) -> Result<Box<dyn Any + Send>> {
+        let deadline = match next.context().get::<Deadline>() {
+            Some(deadline) => *deadline,
+            None => return next.run(request).await,
+        };
+        if deadline.is_exceeded() {
+            return Err(Box::new(MediatorError::DeadlineExceededError));
+        }
+        match crate::runtime::timeout(deadline.remaining(), next.run(request)).await {
+            Some(response) => response,
+            None => Err(Box::new(MediatorError::DeadlineExceededError)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "timeout"))]
+mod test {
+    use super::*;
+    use crate::{ChainableHandler, Mediator, Request, RequestContext};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug)]
+    pub struct Sleep(Duration);
+
+    impl Request<()> for Sleep {}
+
+    fn mediator(calls: Arc<AtomicUsize>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |request: Sleep| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(request.0).await;
+                Ok(())
+            }
+        })
+        .register_behavior(DeadlineBehavior);
+        m
+    }
+
+    #[tokio::test]
+    async fn test_deadline_behavior() {
+        let mut m = mediator(Arc::default());
+        assert!(m.send(Sleep(Duration::from_millis(10))).await.is_ok());
+        let context = RequestContext::new().with(Deadline::after(Duration::from_secs(1)));
+        assert!(m
+            .send_with_context(Sleep(Duration::ZERO), context)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_behavior_exceeded() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut m = mediator(calls.clone());
+        let deadline = Deadline::after(Duration::from_millis(10));
+        let err = m
+            .send_with_context(
+                Sleep(Duration::from_secs(1)),
+                RequestContext::new().with(deadline),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::DeadlineExceededError)
+        );
+        assert!(deadline.is_exceeded());
+
+        let err = m
+            .send_with_context(Sleep(Duration::ZERO), RequestContext::new().with(deadline))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::DeadlineExceededError)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Debug)]
+    pub struct Nested;
+
+    impl Request<String> for Nested {}
+
+    #[derive(Debug)]
+    pub struct OverrunHandler;
+
+    #[async_trait]
+    impl ChainableHandler<Nested, String> for OverrunHandler {
+        async fn handle(&mut self, _request: Nested, mediator: &mut Mediator) -> Result<String> {
+            std::thread::sleep(Duration::from_millis(20));
+            match mediator.send(Sleep(Duration::ZERO)).await {
+                Ok(()) => Ok(String::from("handled")),
+                Err(e) => Ok(e.to_string()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_send_inherits_deadline() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Sleep| {
+            handler_calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        })
+        .register_chainable_handler(OverrunHandler);
+        let context = RequestContext::new().with(Deadline::after(Duration::from_millis(10)));
+        let response = m.send_with_context(Nested, context).await.unwrap();
+        assert_eq!(response, MediatorError::DeadlineExceededError.to_string());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(m.send(Nested).await.unwrap(), "handled");
+    }
+
+    #[tokio::test]
+    async fn test_send_with_timeout_shortened_to_deadline() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Sleep| async move {
+            tokio::time::sleep(request.0).await;
+            Ok(())
+        });
+        let context = RequestContext::new().with(Deadline::after(Duration::from_millis(10)));
+        let err = m
+            .send_with_context(Sleep(Duration::from_secs(1)), context)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::DeadlineExceededError)
+        );
+        let err = m
+            .send_with_timeout(Sleep(Duration::from_secs(1)), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::TimeoutError)
+        );
+    }
+}
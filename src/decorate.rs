@@ -0,0 +1,78 @@
+use crate::{Mediator, MediatorError, Request, RequestHandler};
+
+/// A boxed request handler, as stored by the mediator.
+pub type BoxedHandler<TRequest, TResponse> = Box<dyn RequestHandler<TRequest, TResponse>>;
+
+impl Mediator {
+    /// Wraps the registered request handler of the request with a decorator,
+    /// which receives the handler to delegate to and returns the handler to register instead.
+    ///
+    /// Unlike a pipeline behavior, the decorator only applies to this request, and is kept
+    /// until another handler is registered for it. The default timeout of the handler, if any,
+    /// is kept as well.
+    ///
+    /// Returns `MediatorError::HandlerNotRegisteredError` if no request handler is registered
+    /// for the request, e.g. when it is handled by a chainable handler, and
+    /// `MediatorError::HandlerTypeMismatchError` if it is registered for another response type.
+    pub fn decorate_handler<TRequest, TResponse>(
+        &mut self,
+        decorator: impl FnOnce(BoxedHandler<TRequest, TResponse>) -> BoxedHandler<TRequest, TResponse>,
+    ) -> core::result::Result<&mut Self, MediatorError>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        self.handlers
+            .get_mut::<TRequest, BoxedHandler<TRequest, TResponse>>()?;
+        if let Some(inner) = self
+            .handlers
+            .remove::<TRequest, BoxedHandler<TRequest, TResponse>>()
+        {
+            self.handlers
+                .set::<TRequest, BoxedHandler<TRequest, TResponse>>(decorator(inner));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Result;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Greet(&'static str);
+
+    impl Request<String> for Greet {}
+
+    pub struct Shout(BoxedHandler<Greet, String>);
+
+    #[async_trait]
+    impl RequestHandler<Greet, String> for Shout {
+        async fn handle(&mut self, request: Greet) -> Result<String> {
+            Ok(self.0.handle(request).await?.to_uppercase())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_decorate_handler() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Greet| async move { Ok(format!("hello {}", request.0)) });
+        m.decorate_handler(|inner| Box::new(Shout(inner))).unwrap();
+        assert_eq!(m.send(Greet("world")).await.unwrap(), "HELLO WORLD");
+        assert!(m.has_handler::<Greet, String>());
+    }
+
+    #[test]
+    fn test_mediator_decorate_handler_not_registered() {
+        let mut m = Mediator::new();
+        assert_eq!(
+            m.decorate_handler::<Greet, String>(|inner| Box::new(Shout(inner)))
+                .err(),
+            Some(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Greet>().into()
+            ))
+        );
+    }
+}
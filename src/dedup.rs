@@ -0,0 +1,224 @@
+use async_trait::async_trait;
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Next, PipelineBehavior, Request, Result};
+
+/// The error returned to the requests deduplicated into a request that failed.
+///
+/// Errors cannot be cloned, so only the message of the original error is kept.
+#[derive(Debug, Clone)]
+struct SharedError(String);
+
+impl std::error::Error for SharedError {}
+
+impl std::fmt::Display for SharedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+type InFlight<TResponse> = Shared<oneshot::Receiver<core::result::Result<TResponse, SharedError>>>;
+
+type Sender<TResponse> = oneshot::Sender<core::result::Result<TResponse, SharedError>>;
+
+/// Removes the request from the requests in flight when it completes or is dropped,
+/// before the requests waiting for it are woken up by dropping its sender.
+struct InFlightGuard<'a, TRequest: Hash + Eq, TResponse> {
+    in_flight: &'a Mutex<HashMap<TRequest, InFlight<TResponse>>>,
+    key: TRequest,
+    sender: Option<Sender<TResponse>>,
+}
+
+impl<TRequest: Hash + Eq, TResponse> Drop for InFlightGuard<'_, TRequest, TResponse> {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.key);
+    }
+}
+
+/// A pipeline behavior collapsing identical in-flight requests: a request identical to one
+/// being handled awaits the result of the latter instead of invoking the handler again.
+///
+/// Requests are collapsed across the mediators sharing clones of the behavior, see the
+/// concurrency section of [`Mediator::send`](crate::Mediator::send).
+/// The deduplicated requests receive a clone of the response, or an error with
+/// the message of the original error.
+pub struct DeduplicationBehavior<TRequest, TResponse> {
+    in_flight: Arc<Mutex<HashMap<TRequest, InFlight<TResponse>>>>,
+}
+
+impl<TRequest, TResponse> DeduplicationBehavior<TRequest, TResponse> {
+    /// Creates a new deduplication behavior.
+    pub fn new() -> Self {
+        DeduplicationBehavior {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<TRequest, TResponse> Default for DeduplicationBehavior<TRequest, TResponse> {
+    fn default() -> Self {
+        DeduplicationBehavior::new()
+    }
+}
+
+impl<TRequest, TResponse> Clone for DeduplicationBehavior<TRequest, TResponse> {
+    fn clone(&self) -> Self {
+        DeduplicationBehavior {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+impl<TRequest, TResponse> std::fmt::Debug for DeduplicationBehavior<TRequest, TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeduplicationBehavior")
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse>
+    for DeduplicationBehavior<TRequest, TResponse>
+where
+    TRequest: Request<TResponse> + Hash + Eq + Clone,
+    TResponse: Clone + Send + Sync + 'static,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let sender = loop {
+            let in_flight = match self.in_flight.lock().unwrap().entry(request.clone()) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => {
+                    let (sender, receiver) = oneshot::channel();
+                    entry.insert(receiver.shared());
+                    break sender;
+                }
+            };
+            match in_flight.await {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(e)) => return Err(Box::new(e)),
+                // The request was dropped before completing, and removed from the requests
+                // in flight: handle this one instead, unless another one took its place.
+                Err(oneshot::Canceled) => continue,
+            }
+        };
+        let mut guard = InFlightGuard {
+            in_flight: &self.in_flight,
+            key: request.clone(),
+            sender: Some(sender),
+        };
+        let result = next.run(request).await;
+        let sender = guard.sender.take();
+        drop(guard);
+        let shared = match &result {
+            Ok(response) => Ok(response.clone()),
+            Err(e) => Err(SharedError(e.to_string())),
+        };
+        if let Some(sender) = sender {
+            let _ = sender.send(shared);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Fetch(&'static str);
+
+    impl Request<String> for Fetch {}
+
+    fn mediator(
+        behavior: &DeduplicationBehavior<Fetch, String>,
+        calls: Arc<AtomicUsize>,
+    ) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |request: Fetch| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                match request.0 {
+                    "" => Err("empty key".into()),
+                    key => Ok(key.to_uppercase()),
+                }
+            }
+        })
+        .register_behavior_for(behavior.clone());
+        m
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_behavior_collapses_in_flight_requests() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = DeduplicationBehavior::new();
+        let mut m1 = mediator(&behavior, calls.clone());
+        let mut m2 = mediator(&behavior, calls.clone());
+        let (r1, r2) = tokio::join!(m1.send(Fetch("key")), m2.send(Fetch("key")));
+        assert_eq!(r1.unwrap(), "KEY");
+        assert_eq!(r2.unwrap(), "KEY");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(m1.send(Fetch("key")).await.unwrap(), "KEY");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_deduplication_behavior_on_multiple_threads() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = DeduplicationBehavior::new();
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let mut m = mediator(&behavior, calls.clone());
+                tokio::spawn(async move { m.send(Fetch("key")).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), "KEY");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_behavior_dropped_request() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = DeduplicationBehavior::new();
+        let mut m1 = mediator(&behavior, calls.clone());
+        let mut m2 = mediator(&behavior, calls.clone());
+        let (dropped, r2) = tokio::join!(
+            tokio::time::timeout(Duration::from_millis(10), m1.send(Fetch("key"))),
+            m2.send(Fetch("key"))
+        );
+        assert!(dropped.is_err());
+        assert_eq!(r2.unwrap(), "KEY");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_behavior_shares_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = DeduplicationBehavior::new();
+        let mut m1 = mediator(&behavior, calls.clone());
+        let mut m2 = mediator(&behavior, calls.clone());
+        let (r1, r2) = tokio::join!(m1.send(Fetch("")), m2.send(Fetch("")));
+        assert_eq!(r1.unwrap_err().to_string(), "empty key");
+        assert_eq!(r2.unwrap_err().to_string(), "empty key");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,120 @@
+use futures::channel::oneshot;
+use std::sync::Mutex;
+
+use crate::{MediatorError, SharedMediator};
+
+/// Counts the requests in flight through a shared mediator, so it can be drained.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight(Mutex<InFlightState>);
+
+#[derive(Debug, Default)]
+struct InFlightState {
+    count: usize,
+    draining: bool,
+    drained: Vec<oneshot::Sender<()>>,
+}
+
+/// Marks a request as in flight until it is dropped, even if the request is cancelled.
+pub(crate) struct InFlightGuard<'a>(&'a InFlight);
+
+impl InFlight {
+    /// Marks a request as in flight.
+    ///
+    /// Returns `MediatorError::ShuttingDownError` once the mediator is being drained.
+    pub(crate) fn start(&self) -> core::result::Result<InFlightGuard<'_>, MediatorError> {
+        let mut state = self.0.lock().unwrap();
+        if state.draining {
+            return Err(MediatorError::ShuttingDownError);
+        }
+        state.count += 1;
+        Ok(InFlightGuard(self))
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0 .0.lock().unwrap();
+        state.count -= 1;
+        if state.count == 0 {
+            for drained in state.drained.drain(..) {
+                let _ = drained.send(());
+            }
+        }
+    }
+}
+
+impl SharedMediator {
+    /// Waits until every request in flight is handled, e.g. for a graceful shutdown.
+    ///
+    /// The requests, notifications and queries sent through any clone of the mediator
+    /// once it is being drained fail with `MediatorError::ShuttingDownError`.
+    pub async fn drain(&self) {
+        let drained = {
+            let mut state = self.in_flight.0.lock().unwrap();
+            state.draining = true;
+            if state.count == 0 {
+                return;
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.drained.push(sender);
+            receiver
+        };
+        let _ = drained.await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Mediator, Request};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    #[derive(Debug)]
+    pub struct Work;
+
+    impl Request<()> for Work {}
+
+    #[tokio::test]
+    async fn test_shared_mediator_drain() {
+        let done = Arc::new(AtomicBool::new(false));
+        let handler_done = done.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Work| {
+            let done = handler_done.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                done.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        let shared = SharedMediator::new(m);
+
+        let task = tokio::spawn({
+            let shared = shared.clone();
+            async move { shared.send(Work).await }
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        shared.drain().await;
+        assert!(done.load(Ordering::SeqCst));
+        assert!(task.await.unwrap().is_ok());
+
+        let err = shared.send(Work).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::ShuttingDownError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shared_mediator_drain_without_requests() {
+        let shared = SharedMediator::new(Mediator::new());
+        shared.drain().await;
+        shared.drain().await;
+    }
+}
@@ -1,17 +1,282 @@
+use std::{borrow::Cow, sync::Arc};
+
+use crate::ValidationFailure;
+
 /// This error is returned whenever something goes wrong within the mediator itself.
-#[derive(Debug, PartialEq)]
+///
+/// `send` returns it boxed, along with the errors of the handlers. `Mediator::send_typed`
+/// returns every error as a `MediatorError`, wrapping the errors of the handler into
+/// `MediatorError::HandlerError`.
+#[derive(Debug, Clone)]
 pub enum MediatorError {
-    /// The handler is not registerd.
+    /// The handler is not registerd, for the request whose type name, or name, is given.
     /// Please register the handler before using it.
-    HandlerNotRegisteredError,
+    HandlerNotRegisteredError(Cow<'static, str>),
+    /// A handler is already registered for the request.
+    HandlerAlreadyRegisteredError,
+    /// A handler is registered for the request, but with another type,
+    /// e.g. for another response type.
+    HandlerTypeMismatchError,
+    /// The handler did not complete in time.
+    TimeoutError,
+    /// The circuit breaker is open, so the request was not handled.
+    CircuitOpenError,
+    /// The mediator is being drained and no longer accepts requests.
+    ShuttingDownError,
+    /// Too many requests are already being handled concurrently.
+    ConcurrencyLimitExceededError,
+    /// The deadline of the request was exceeded before the handler completed.
+    DeadlineExceededError,
+    /// The request was cancelled before the handler completed.
+    CancelledError,
+    /// The request is not of the type expected by the handler.
+    RequestTypeMismatchError,
+    /// The request was sent from its own handler, which is busy handling it.
+    RecursiveRequestError,
+    /// The handler is registered but cannot handle requests for now,
+    /// e.g. because a subsystem it depends on is not initialized.
+    HandlerUnavailableError,
+    /// Several handlers returned an error.
+    ///
+    /// The errors are shared so the error can be cloned.
+    AggregateError(Vec<Arc<dyn std::error::Error + Send + Sync>>),
+    /// The handler could not be built, with the error returned by its factory.
+    HandlerInitFailedError(Arc<dyn std::error::Error + Send + Sync>),
+    /// The request is invalid.
+    ValidationError(Vec<ValidationFailure>),
+    /// No handler is registered for the notification, whose type name is given.
+    NoSubscribersError(&'static str),
+    /// The handler panicked, with the panic message.
+    HandlerPanickedError(String),
+    /// A request with the same idempotency key is being handled.
+    DuplicateRequestError,
+    /// The handler returned an error, to match on every error as a `MediatorError`.
+    ///
+    /// The error is shared so the error can be cloned.
+    HandlerError(Arc<dyn std::error::Error + Send + Sync>),
+    /// The handler is disabled, e.g. by a feature flag, so the request was not handled.
+    HandlerDisabledError,
+    /// The request was produced with a schema version which is no longer supported,
+    /// whose number is given.
+    UnsupportedVersionError(u32),
 }
 
-impl std::error::Error for MediatorError {}
+impl std::error::Error for MediatorError {
+    /// Returns the first error of an aggregate error, or the error of a handler or its factory.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MediatorError::AggregateError(errors) => errors
+                .first()
+                .map(|e| &**e as &(dyn std::error::Error + 'static)),
+            MediatorError::HandlerInitFailedError(e) | MediatorError::HandlerError(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+/// Errors held by a variant are compared by their message.
+impl PartialEq for MediatorError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MediatorError::AggregateError(a), MediatorError::AggregateError(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.to_string() == b.to_string())
+            }
+            (
+                MediatorError::HandlerInitFailedError(a),
+                MediatorError::HandlerInitFailedError(b),
+            ) => a.to_string() == b.to_string(),
+            (MediatorError::HandlerError(a), MediatorError::HandlerError(b)) => {
+                a.to_string() == b.to_string()
+            }
+            (MediatorError::ValidationError(a), MediatorError::ValidationError(b)) => a == b,
+            (
+                MediatorError::HandlerNotRegisteredError(a),
+                MediatorError::HandlerNotRegisteredError(b),
+            ) => a == b,
+            (MediatorError::NoSubscribersError(a), MediatorError::NoSubscribersError(b)) => a == b,
+            (MediatorError::HandlerPanickedError(a), MediatorError::HandlerPanickedError(b)) => {
+                a == b
+            }
+            (
+                MediatorError::UnsupportedVersionError(a),
+                MediatorError::UnsupportedVersionError(b),
+            ) => a == b,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
 
 impl std::fmt::Display for MediatorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MediatorError::HandlerNotRegisteredError => write!(f, "Handler not registered"),
+            MediatorError::HandlerNotRegisteredError(request) => {
+                write!(f, "Handler not registered for `{}`", request)
+            }
+            MediatorError::HandlerAlreadyRegisteredError => {
+                write!(f, "Handler already registered")
+            }
+            MediatorError::HandlerTypeMismatchError => write!(f, "Handler type mismatch"),
+            MediatorError::TimeoutError => write!(f, "Handler timed out"),
+            MediatorError::CircuitOpenError => write!(f, "Circuit open"),
+            MediatorError::ShuttingDownError => write!(f, "Mediator shutting down"),
+            MediatorError::ConcurrencyLimitExceededError => {
+                write!(f, "Concurrency limit exceeded")
+            }
+            MediatorError::DeadlineExceededError => write!(f, "Deadline exceeded"),
+            MediatorError::CancelledError => write!(f, "Request cancelled"),
+            MediatorError::RequestTypeMismatchError => write!(f, "Request type mismatch"),
+            MediatorError::RecursiveRequestError => write!(f, "Request sent recursively"),
+            MediatorError::HandlerUnavailableError => write!(f, "Handler unavailable"),
+            MediatorError::AggregateError(errors) => {
+                write!(f, "{} handlers failed", errors.len())?;
+                for error in errors {
+                    write!(f, "; {}", error)?;
+                }
+                Ok(())
+            }
+            MediatorError::HandlerInitFailedError(e) => {
+                write!(f, "Handler initialization failed: {}", e)
+            }
+            MediatorError::ValidationError(failures) => {
+                write!(f, "Validation failed")?;
+                for failure in failures {
+                    write!(f, "; {}", failure)?;
+                }
+                Ok(())
+            }
+            MediatorError::NoSubscribersError(notification) => {
+                write!(f, "No handler registered for notification {}", notification)
+            }
+            MediatorError::HandlerPanickedError(message) => {
+                write!(f, "Handler panicked: {}", message)
+            }
+            MediatorError::DuplicateRequestError => write!(f, "Duplicate request in progress"),
+            MediatorError::HandlerError(e) => write!(f, "Handler failed: {}", e),
+            MediatorError::HandlerDisabledError => write!(f, "Handler disabled"),
+            MediatorError::UnsupportedVersionError(version) => {
+                write!(f, "Unsupported request version {}", version)
+            }
+        }
+    }
+}
+
+/// Converts the error returned by `send`, as `Mediator::send_typed` does,
+/// keeping the errors of the mediator itself and wrapping the errors of the handler
+/// into `MediatorError::HandlerError`.
+///
+/// Every error cannot be converted with a blanket `From` implementation,
+/// as it would conflict with the conversion of a `MediatorError` into itself.
+impl From<Box<dyn std::error::Error + Send + Sync>> for MediatorError {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match error.downcast::<MediatorError>() {
+            Ok(e) => *e,
+            Err(e) => MediatorError::HandlerError(Arc::from(e)),
+        }
+    }
+}
+
+/// This error separates the errors of the mediator itself from the errors returned by a handler.
+///
+/// It is built from the error returned by `send`, so callers can `match` on it
+/// instead of downcasting.
+#[derive(Debug)]
+pub enum SendError<E = Box<dyn std::error::Error + Send + Sync>> {
+    /// The mediator failed to dispatch the request.
+    Mediator(MediatorError),
+    /// The handler returned an error.
+    Handler(E),
+}
+
+impl SendError {
+    /// Attempts to downcast the handler error to a concrete type.
+    ///
+    /// Returns the original error if the handler error is of another type.
+    pub fn downcast<E>(self) -> Result<SendError<E>, SendError>
+    where
+        E: std::error::Error + 'static,
+    {
+        match self {
+            SendError::Mediator(e) => Ok(SendError::Mediator(e)),
+            SendError::Handler(e) => match e.downcast::<E>() {
+                Ok(e) => Ok(SendError::Handler(*e)),
+                Err(e) => Err(SendError::Handler(e)),
+            },
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for SendError {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match error.downcast::<MediatorError>() {
+            Ok(e) => SendError::Mediator(*e),
+            Err(e) => SendError::Handler(e),
+        }
+    }
+}
+
+impl<E> std::error::Error for SendError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SendError::Mediator(e) => Some(e),
+            SendError::Handler(e) => Some(e),
+        }
+    }
+}
+
+impl<E> std::fmt::Display for SendError<E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Mediator(e) => write!(f, "Mediator error: {}", e),
+            SendError::Handler(e) => write!(f, "Handler error: {}", e),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_mediator_error_clone() {
+        let error = MediatorError::AggregateError(vec![Arc::from(
+            Box::<dyn Error + Send + Sync>::from("first"),
+        )]);
+        assert_eq!(error.clone(), error);
+    }
+
+    #[test]
+    fn test_mediator_error_source() {
+        let error = MediatorError::AggregateError(vec![
+            Arc::from(Box::<dyn Error + Send + Sync>::from("first")),
+            Arc::from(Box::<dyn Error + Send + Sync>::from("second")),
+        ]);
+        assert_eq!(
+            error.source().map(|e| e.to_string()).as_deref(),
+            Some("first")
+        );
+        assert!(MediatorError::TimeoutError.source().is_none());
+    }
+
+    #[test]
+    fn test_mediator_error_from_boxed_error() {
+        let error = MediatorError::from(Box::<dyn Error + Send + Sync>::from("failed"));
+        assert_eq!(
+            error,
+            MediatorError::HandlerError(Arc::from(Box::<dyn Error + Send + Sync>::from("failed")))
+        );
+        assert_eq!(error.to_string(), "Handler failed: failed");
+        assert_eq!(
+            error.source().map(|e| e.to_string()).as_deref(),
+            Some("failed")
+        );
+        let error: Box<dyn Error + Send + Sync> = Box::new(MediatorError::TimeoutError);
+        assert_eq!(MediatorError::from(error), MediatorError::TimeoutError);
+    }
+}
@@ -0,0 +1,70 @@
+use std::{error::Error, sync::Arc};
+
+use crate::Mediator;
+
+type ErrorHookFn = dyn Fn(&dyn Error) + Send + Sync;
+
+#[derive(Default, Clone)]
+pub(crate) struct ErrorHook(pub(crate) Option<Arc<ErrorHookFn>>);
+
+impl std::fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ErrorHook").field(&self.0.is_some()).finish()
+    }
+}
+
+impl Mediator {
+    /// Sets the hook called with the error of every failed request, e.g. to log it.
+    ///
+    /// The hook only observes the error, which is still returned to the caller.
+    /// Setting another hook replaces the previous one.
+    pub fn set_error_hook<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: Fn(&dyn Error) + Send + Sync + 'static,
+    {
+        self.error_hook = ErrorHook(Some(Arc::new(hook)));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MediatorError, Request};
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[tokio::test]
+    async fn test_mediator_error_hook() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        let hook_errors = errors.clone();
+        m.set_error_hook(move |e| hook_errors.lock().unwrap().push(e.to_string()));
+
+        let err = m.send(Ping).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Ping>().into()
+            ))
+        );
+
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+        m.register_handler_fn(|_: Ping| async { Err::<String, _>("failed".into()) });
+        assert!(m.send(Ping).await.is_err());
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![
+                MediatorError::HandlerNotRegisteredError(std::any::type_name::<Ping>().into())
+                    .to_string(),
+                String::from("failed")
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+use crate::{Mediator, Request, Result};
+
+/// Sends requests to a mediator behind a lock, locking it internally.
+///
+/// Handlers take `&mut self`, so `send` holds the lock for the whole duration of the request:
+/// a handler sending another request through the same lock deadlocks. `query` only holds
+/// the lock to look up the read handler, and releases it before the handler is awaited.
+///
+/// The trait is implemented for `Arc<futures::lock::Mutex<Mediator>>`, and with the `tokio`
+/// feature for `Arc<tokio::sync::Mutex<Mediator>>` and `Arc<tokio::sync::RwLock<Mediator>>`,
+/// whose `query` only takes the read lock. The guard of `std::sync::Mutex` is not `Send`,
+/// so it cannot be held across the handler.
+#[async_trait]
+pub trait MediatorExt {
+    /// Locks the mediator and sends a request to it, holding the lock until the response.
+    async fn send<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static;
+
+    /// Sends a request to its read handler, only locking the mediator to look the handler up.
+    async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static;
+}
+
+#[async_trait]
+impl MediatorExt for Arc<Mutex<Mediator>> {
+    async fn send<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.lock().await.send(request).await
+    }
+
+    async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = self.lock().await.read_handler()?;
+        handler.handle(request).await
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl MediatorExt for Arc<tokio::sync::Mutex<Mediator>> {
+    async fn send<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.lock().await.send(request).await
+    }
+
+    async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = self.lock().await.read_handler()?;
+        handler.handle(request).await
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl MediatorExt for Arc<tokio::sync::RwLock<Mediator>> {
+    async fn send<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.write().await.send(request).await
+    }
+
+    async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = self.read().await.read_handler()?;
+        handler.handle(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ReadHandler, RequestHandler};
+
+    #[derive(Debug)]
+    pub struct Increment;
+
+    impl Request<usize> for Increment {}
+
+    #[derive(Debug, Default)]
+    pub struct IncrementHandler(usize);
+
+    #[async_trait]
+    impl RequestHandler<Increment, usize> for IncrementHandler {
+        async fn handle(&mut self, _request: Increment) -> Result<usize> {
+            self.0 += 1;
+            Ok(self.0)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Nested;
+
+    impl Request<usize> for Nested {}
+
+    #[derive(Debug)]
+    pub struct NestedHandler(Arc<Mutex<Mediator>>);
+
+    #[async_trait]
+    impl ReadHandler<Nested, usize> for NestedHandler {
+        async fn handle(&self, _request: Nested) -> Result<usize> {
+            self.0.send(Increment).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_ext_send() {
+        let mut m = Mediator::new();
+        m.register_handler(IncrementHandler::default());
+        let m = Arc::new(Mutex::new(m));
+        assert_eq!(m.send(Increment).await.unwrap(), 1);
+        assert_eq!(m.send(Increment).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_ext_query_releases_the_lock() {
+        let m = Arc::new(Mutex::new(Mediator::new()));
+        m.lock()
+            .await
+            .register_handler(IncrementHandler::default())
+            .register_read_handler(NestedHandler(m.clone()));
+        assert_eq!(m.query(Nested).await.unwrap(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_mediator_ext_rw_lock() {
+        let mut m = Mediator::new();
+        m.register_handler(IncrementHandler::default())
+            .register_read_handler(Mutex::new(IncrementHandler::default()));
+        let m = Arc::new(tokio::sync::RwLock::new(m));
+        assert_eq!(m.send(Increment).await.unwrap(), 1);
+        assert_eq!(m.query(Increment).await.unwrap(), 1);
+    }
+}
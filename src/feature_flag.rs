@@ -0,0 +1,156 @@
+use async_trait::async_trait;
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::{AnyNext, GlobalPipelineBehavior, MediatorError, Request, Result};
+
+type Fallback = Box<dyn Fn() -> Box<dyn Any + Send> + Send + Sync>;
+
+/// A global pipeline behavior disabling the handlers of some request types at runtime,
+/// e.g. for gradual rollouts.
+///
+/// The flag is a function called with the type name of every request, returning whether its
+/// handler is enabled. A request whose handler is disabled is not handled: its fallback
+/// response is returned if it has one, otherwise it fails with
+/// `MediatorError::HandlerDisabledError`.
+pub struct FeatureFlagBehavior {
+    is_enabled: Box<dyn Fn(&str) -> bool + Send + Sync>,
+    /// The fallbacks with the type name of their request, by request and response type.
+    fallbacks: HashMap<(TypeId, TypeId), (&'static str, Fallback)>,
+}
+
+impl FeatureFlagBehavior {
+    /// Creates a new feature flag behavior, enabling the handlers for which the flag is `true`.
+    pub fn new(is_enabled: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        FeatureFlagBehavior {
+            is_enabled: Box::new(is_enabled),
+            fallbacks: HashMap::new(),
+        }
+    }
+
+    /// Sets the response returned for the request when its handler is disabled.
+    pub fn with_fallback<TRequest, TResponse>(
+        mut self,
+        fallback: impl Fn() -> TResponse + Send + Sync + 'static,
+    ) -> Self
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.fallbacks.insert(
+            (TypeId::of::<TRequest>(), TypeId::of::<TResponse>()),
+            (
+                type_name::<TRequest>(),
+                Box::new(move || Box::new(fallback())),
+            ),
+        );
+        self
+    }
+}
+
+impl std::fmt::Debug for FeatureFlagBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeatureFlagBehavior")
+            .field(
+                "fallbacks",
+                &self
+                    .fallbacks
+                    .values()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl GlobalPipelineBehavior for FeatureFlagBehavior {
+    async fn handle(
+        &mut self,
+        request: Box<dyn Any + Send>,
+        mut next: AnyNext<'_>,
+    ) -> Result<Box<dyn Any + Send>> {
+        let request_type_name = next.request_type_name();
+        if (self.is_enabled)(request_type_name) {
+            return next.run(request).await;
+        }
+        match self.fallbacks.get(&next.type_ids()) {
+            Some((_, fallback)) => Ok(fallback()),
+            None => Err(Box::new(MediatorError::HandlerDisabledError)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug)]
+    pub struct Recommend;
+
+    impl Request<Vec<String>> for Recommend {}
+
+    #[derive(Debug)]
+    pub struct Checkout;
+
+    impl Request<String> for Checkout {}
+
+    #[tokio::test]
+    async fn test_feature_flag_behavior() {
+        let enabled = Arc::new(AtomicBool::new(false));
+        let flag = enabled.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Recommend| async { Ok(vec![String::from("brazier")]) })
+            .register_handler_fn(|_: Checkout| async { Ok(String::from("paid")) })
+            .register_behavior(
+                FeatureFlagBehavior::new(move |request_type_name| {
+                    flag.load(Ordering::SeqCst) || !request_type_name.ends_with("Recommend")
+                })
+                .with_fallback::<Recommend, _>(Vec::new),
+            );
+        assert!(m.send(Recommend).await.unwrap().is_empty());
+        assert_eq!(m.send(Checkout).await.unwrap(), "paid");
+        enabled.store(true, Ordering::SeqCst);
+        assert_eq!(m.send(Recommend).await.unwrap(), vec!["brazier"]);
+    }
+
+    #[tokio::test]
+    async fn test_feature_flag_behavior_without_fallback() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Checkout| async { Ok(String::from("paid")) })
+            .register_behavior(FeatureFlagBehavior::new(|_| false));
+        let err = m.send(Checkout).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerDisabledError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feature_flag_behavior_fallback_per_response_type() {
+        #[derive(Debug)]
+        pub struct Quote;
+
+        impl Request<u64> for Quote {}
+
+        impl Request<String> for Quote {}
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Quote| async { Ok(String::from("42")) })
+            .register_behavior(
+                FeatureFlagBehavior::new(|_| false).with_fallback::<Quote, u64>(|| 0),
+            );
+        let err = m.send::<_, String>(Quote).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerDisabledError)
+        );
+    }
+}
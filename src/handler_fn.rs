@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use std::{future::Future, marker::PhantomData};
+
+use crate::{Mediator, Request, RequestHandler, Result};
+
+/// Adapts a closure into a request handler.
+struct HandlerFn<F, TRequest, TResponse> {
+    f: F,
+    _marker: PhantomData<fn(TRequest) -> TResponse>,
+}
+
+#[async_trait]
+impl<F, Fut, TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for HandlerFn<F, TRequest, TResponse>
+where
+    F: FnMut(TRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<TResponse>> + Send,
+    TRequest: Request<TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        (self.f)(request).await
+    }
+}
+
+impl Mediator {
+    /// Registers a closure as a request handler.
+    ///
+    /// The closure and the future it returns must be `'static`,
+    /// so any state captured by the closure has to be moved into it.
+    pub fn register_handler_fn<TRequest, TResponse, F, Fut>(&mut self, f: F) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+        F: FnMut(TRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<TResponse>> + Send + 'static,
+    {
+        self.register_handler(HandlerFn {
+            f,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub struct Increment(i64);
+
+    impl Request<i64> for Increment {}
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_fn() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Increment| async move { Ok(request.0 + 1) });
+        assert_eq!(m.send(Increment(41)).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_fn_with_state() {
+        let mut total = 0;
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |request: Increment| {
+            total += request.0;
+            async move { Ok(total) }
+        });
+        assert_eq!(m.send(Increment(2)).await.unwrap(), 2);
+        assert_eq!(m.send(Increment(3)).await.unwrap(), 5);
+    }
+}
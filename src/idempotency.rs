@@ -0,0 +1,427 @@
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{MediatorError, Next, PipelineBehavior, Request, Result};
+
+/// The idempotency key trait. Requests implementing it opt in to the idempotency behavior.
+pub trait HasIdempotencyKey {
+    /// Returns the key identifying the request across deliveries, e.g. the id of a message.
+    fn idempotency_key(&self) -> String;
+}
+
+/// The state of an idempotency key, returned when it is claimed in the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyClaim<TResponse> {
+    /// The key was not claimed yet and is now claimed by the request, which is handled.
+    Claimed,
+    /// A request with the key is being handled.
+    InProgress,
+    /// A request with the key was handled, with the given response.
+    Completed(TResponse),
+}
+
+/// The idempotency store trait. A store keeps the state of the idempotency keys,
+/// e.g. in memory or in Redis, so the idempotency behavior can be shared across processes.
+#[async_trait]
+pub trait IdempotencyStore<TResponse>: Send + Sync {
+    /// Claims the key, unless it is already claimed or completed.
+    /// The check and the claim must be atomic.
+    async fn claim(&self, key: &str) -> Result<IdempotencyClaim<TResponse>>;
+
+    /// Stores the response of the request which claimed the key.
+    async fn complete(&self, key: &str, response: TResponse) -> Result<()>;
+
+    /// Releases the key after the request which claimed it failed, so it can be retried.
+    async fn release(&self, key: &str) -> Result<()>;
+
+    /// Waits until the request which claimed the key is completed or released.
+    ///
+    /// Defaults to `MediatorError::DuplicateRequestError`, for stores unable to wait.
+    async fn wait(&self, _key: &str) -> Result<()> {
+        Err(Box::new(MediatorError::DuplicateRequestError))
+    }
+
+    /// Releases the key after the request which claimed it was dropped, before completing.
+    ///
+    /// This cannot await, so it defaults to nothing: stores relying on async I/O should
+    /// expire the claims instead, e.g. with the `PX` option of the Redis `SET` command.
+    fn abandon(&self, _key: &str) {}
+}
+
+enum Entry<TResponse> {
+    InProgress(Vec<oneshot::Sender<()>>),
+    Completed(TResponse),
+}
+
+/// An idempotency store keeping the idempotency keys in memory.
+///
+/// Completed responses are kept until they are removed, so the memory grows with the
+/// number of keys: remove them once the messages can no longer be redelivered.
+pub struct InMemoryIdempotencyStore<TResponse> {
+    entries: Mutex<HashMap<String, Entry<TResponse>>>,
+}
+
+impl<TResponse> InMemoryIdempotencyStore<TResponse> {
+    /// Creates a new empty store.
+    pub fn new() -> Self {
+        InMemoryIdempotencyStore {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the number of claimed or completed keys.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no key is claimed or completed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes a completed key, so a request with the key is handled again.
+    ///
+    /// A key being handled is kept. Returns whether the key was removed.
+    pub fn remove(&self, key: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(Entry::Completed(_)) = entries.get(key) {
+            entries.remove(key);
+            return true;
+        }
+        false
+    }
+}
+
+impl<TResponse> Default for InMemoryIdempotencyStore<TResponse> {
+    fn default() -> Self {
+        InMemoryIdempotencyStore::new()
+    }
+}
+
+impl<TResponse> std::fmt::Debug for InMemoryIdempotencyStore<TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryIdempotencyStore")
+            .field("entries", &self.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TResponse> IdempotencyStore<TResponse> for InMemoryIdempotencyStore<TResponse>
+where
+    TResponse: Clone + Send + Sync,
+{
+    async fn claim(&self, key: &str) -> Result<IdempotencyClaim<TResponse>> {
+        let mut entries = self.entries.lock().unwrap();
+        Ok(match entries.get(key) {
+            Some(Entry::InProgress(_)) => IdempotencyClaim::InProgress,
+            Some(Entry::Completed(response)) => IdempotencyClaim::Completed(response.clone()),
+            None => {
+                entries.insert(String::from(key), Entry::InProgress(Vec::new()));
+                IdempotencyClaim::Claimed
+            }
+        })
+    }
+
+    async fn complete(&self, key: &str, response: TResponse) -> Result<()> {
+        // Dropping the senders wakes up the waiting requests.
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(String::from(key), Entry::Completed(response));
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        self.abandon(key);
+        Ok(())
+    }
+
+    async fn wait(&self, key: &str) -> Result<()> {
+        let receiver = match self.entries.lock().unwrap().get_mut(key) {
+            Some(Entry::InProgress(waiting)) => {
+                let (sender, receiver) = oneshot::channel();
+                waiting.push(sender);
+                receiver
+            }
+            _ => return Ok(()),
+        };
+        let _ = receiver.await;
+        Ok(())
+    }
+
+    fn abandon(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(Entry::InProgress(_)) = entries.get(key) {
+            entries.remove(key);
+        }
+    }
+}
+
+/// What the idempotency behavior does with a request whose key is claimed
+/// by a request being handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Waits for the request being handled, then returns its response, or handles
+    /// the request if the other one failed. Stores unable to wait reject the request.
+    #[default]
+    Wait,
+    /// Fails with `MediatorError::DuplicateRequestError`.
+    Reject,
+}
+
+/// A pipeline behavior handling requests at most once per idempotency key,
+/// e.g. for messages delivered at least once.
+///
+/// The response to a request is stored with its key, and returned to the requests with the
+/// same key without invoking the handler. Errors are not stored, so a failed request can be
+/// retried. Requests whose key is claimed by a request being handled follow the
+/// `DuplicatePolicy`. Clones of the behavior share the same store.
+pub struct IdempotencyBehavior<TStore> {
+    store: Arc<TStore>,
+    policy: DuplicatePolicy,
+}
+
+impl<TStore> IdempotencyBehavior<TStore> {
+    /// Creates a new idempotency behavior keeping the keys in the store,
+    /// waiting for duplicates being handled.
+    pub fn new(store: TStore) -> Self {
+        IdempotencyBehavior {
+            store: Arc::new(store),
+            policy: DuplicatePolicy::default(),
+        }
+    }
+
+    /// Sets what to do with the requests whose key is claimed by a request being handled.
+    pub fn with_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the store of the idempotency keys.
+    pub fn store(&self) -> &TStore {
+        &self.store
+    }
+}
+
+impl<TStore> Clone for IdempotencyBehavior<TStore> {
+    fn clone(&self) -> Self {
+        IdempotencyBehavior {
+            store: self.store.clone(),
+            policy: self.policy,
+        }
+    }
+}
+
+impl<TStore> std::fmt::Debug for IdempotencyBehavior<TStore> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyBehavior")
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Abandons the claimed key if the request is dropped before it completes,
+/// or if the store fails to complete or release it.
+struct ClaimGuard<'a, TResponse> {
+    store: &'a dyn IdempotencyStore<TResponse>,
+    key: &'a str,
+    completed: bool,
+}
+
+impl<TResponse> Drop for ClaimGuard<'_, TResponse> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.abandon(self.key);
+        }
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse, TStore> PipelineBehavior<TRequest, TResponse>
+    for IdempotencyBehavior<TStore>
+where
+    TRequest: Request<TResponse> + HasIdempotencyKey,
+    TResponse: Clone + Send + 'static,
+    TStore: IdempotencyStore<TResponse> + 'static,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let key = request.idempotency_key();
+        loop {
+            match self.store.claim(&key).await? {
+                IdempotencyClaim::Claimed => break,
+                IdempotencyClaim::Completed(response) => return Ok(response),
+                IdempotencyClaim::InProgress => match self.policy {
+                    DuplicatePolicy::Wait => self.store.wait(&key).await?,
+                    DuplicatePolicy::Reject => {
+                        return Err(Box::new(MediatorError::DuplicateRequestError))
+                    }
+                },
+            }
+        }
+        let mut guard = ClaimGuard {
+            store: &*self.store,
+            key: &key,
+            completed: false,
+        };
+        let result = next.run(request).await;
+        match &result {
+            Ok(response) => self.store.complete(&key, response.clone()).await?,
+            Err(_) => self.store.release(&key).await?,
+        }
+        guard.completed = true;
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[derive(Debug)]
+    pub struct Charge {
+        message_id: &'static str,
+        amount: u64,
+    }
+
+    impl Request<u64> for Charge {}
+
+    impl HasIdempotencyKey for Charge {
+        fn idempotency_key(&self) -> String {
+            String::from(self.message_id)
+        }
+    }
+
+    type Behavior = IdempotencyBehavior<InMemoryIdempotencyStore<u64>>;
+
+    fn mediator(behavior: &Behavior, calls: Arc<AtomicUsize>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |request: Charge| {
+            let calls = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                match request.amount {
+                    0 => Err("nothing to charge".into()),
+                    amount => Ok(amount * 100 + calls as u64),
+                }
+            }
+        })
+        .register_behavior_for::<Charge, u64, _>(behavior.clone());
+        m
+    }
+
+    fn charge(message_id: &'static str, amount: u64) -> Charge {
+        Charge { message_id, amount }
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_behavior_replay() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = IdempotencyBehavior::new(InMemoryIdempotencyStore::new());
+        let mut m = mediator(&behavior, calls.clone());
+        assert_eq!(m.send(charge("a", 1)).await.unwrap(), 101);
+        assert_eq!(m.send(charge("a", 1)).await.unwrap(), 101);
+        assert_eq!(m.send(charge("b", 2)).await.unwrap(), 202);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(behavior.store().remove("a"));
+        assert_eq!(m.send(charge("a", 1)).await.unwrap(), 103);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_behavior_retries_failures() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = IdempotencyBehavior::new(InMemoryIdempotencyStore::new());
+        let mut m = mediator(&behavior, calls.clone());
+        assert!(m.send(charge("a", 0)).await.is_err());
+        assert!(behavior.store().is_empty());
+        assert!(m.send(charge("a", 0)).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_behavior_concurrent_duplicates() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = IdempotencyBehavior::new(InMemoryIdempotencyStore::new());
+        let mut m1 = mediator(&behavior, calls.clone());
+        let mut m2 = mediator(&behavior, calls.clone());
+        let (r1, r2) = tokio::join!(m1.send(charge("a", 1)), m2.send(charge("a", 1)));
+        assert_eq!(r1.unwrap(), 101);
+        assert_eq!(r2.unwrap(), 101);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let behavior = behavior.with_policy(DuplicatePolicy::Reject);
+        let mut m1 = mediator(&behavior, calls.clone());
+        let mut m2 = mediator(&behavior, calls.clone());
+        let (r1, r2) = tokio::join!(m1.send(charge("b", 1)), m2.send(charge("b", 1)));
+        assert_eq!(r1.unwrap(), 102);
+        assert_eq!(
+            r2.unwrap_err().downcast_ref::<MediatorError>(),
+            Some(&MediatorError::DuplicateRequestError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_behavior_store_failure() {
+        #[derive(Debug, Default)]
+        pub struct UnavailableStore(InMemoryIdempotencyStore<u64>);
+
+        #[async_trait]
+        impl IdempotencyStore<u64> for UnavailableStore {
+            async fn claim(&self, key: &str) -> Result<IdempotencyClaim<u64>> {
+                self.0.claim(key).await
+            }
+
+            async fn complete(&self, _key: &str, _response: u64) -> Result<()> {
+                Err("store unavailable".into())
+            }
+
+            async fn release(&self, key: &str) -> Result<()> {
+                self.0.release(key).await
+            }
+
+            fn abandon(&self, key: &str) {
+                self.0.abandon(key)
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = IdempotencyBehavior::new(UnavailableStore::default());
+        let mut m = Mediator::new();
+        let handler_calls = calls.clone();
+        m.register_handler_fn(move |request: Charge| {
+            handler_calls.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(request.amount) }
+        })
+        .register_behavior_for::<Charge, u64, _>(behavior.clone());
+        for _ in 0..2 {
+            let err = m.send(charge("a", 1)).await.unwrap_err();
+            assert_eq!(err.to_string(), "store unavailable");
+            assert!(behavior.store().0.is_empty());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_behavior_dropped_request() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let behavior = IdempotencyBehavior::new(InMemoryIdempotencyStore::new());
+        let mut m = mediator(&behavior, calls.clone());
+        let dropped = tokio::time::timeout(Duration::from_millis(5), m.send(charge("a", 1))).await;
+        assert!(dropped.is_err());
+        assert!(behavior.store().is_empty());
+        assert_eq!(m.send(charge("a", 1)).await.unwrap(), 102);
+    }
+}
@@ -0,0 +1,239 @@
+use futures::future::BoxFuture;
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result};
+
+/// Sends a type-erased request through the mediator, as `Mediator::send_any` does.
+pub(crate) type SendAny =
+    for<'a> fn(&'a mut Mediator, Box<dyn Any + Send>) -> BoxFuture<'a, Result<Box<dyn Any + Send>>>;
+
+/// Calls `on_shutdown` on the handler registered for a request type, as `Mediator::shutdown` does.
+pub(crate) type Shutdown = for<'a> fn(&'a mut Mediator) -> BoxFuture<'a, ()>;
+
+/// Downcasts the request, sends it and boxes the response.
+fn send_any<TRequest, TResponse>(
+    mediator: &mut Mediator,
+    request: Box<dyn Any + Send>,
+) -> BoxFuture<'_, Result<Box<dyn Any + Send>>>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    Box::pin(async move {
+        let request = match request.downcast::<TRequest>() {
+            Ok(request) => *request,
+            Err(_) => return Err(Box::new(MediatorError::RequestTypeMismatchError) as _),
+        };
+        let response = mediator.send::<TRequest, TResponse>(request).await?;
+        Ok(Box::new(response) as Box<dyn Any + Send>)
+    })
+}
+
+/// Calls `on_shutdown` on the request handler, unless it is a chainable handler.
+fn shutdown<TRequest, TResponse>(mediator: &mut Mediator) -> BoxFuture<'_, ()>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    Box::pin(async move {
+        if let Ok(handler) = mediator
+            .handlers
+            .get_mut::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()
+        {
+            handler.on_shutdown().await;
+        }
+    })
+}
+
+/// Describes a registered request handler.
+#[derive(Clone, Copy)]
+pub struct HandlerInfo {
+    request_type_name: &'static str,
+    response_type_id: TypeId,
+    response_type_name: &'static str,
+    pub(crate) send_any: SendAny,
+    pub(crate) shutdown: Shutdown,
+}
+
+impl HandlerInfo {
+    pub(crate) fn of<TRequest, TResponse>() -> Self
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        HandlerInfo {
+            request_type_name: type_name::<TRequest>(),
+            response_type_id: TypeId::of::<TResponse>(),
+            response_type_name: type_name::<TResponse>(),
+            send_any: send_any::<TRequest, TResponse>,
+            shutdown: shutdown::<TRequest, TResponse>,
+        }
+    }
+
+    /// Returns the type name of the request.
+    pub fn request_type_name(&self) -> &'static str {
+        self.request_type_name
+    }
+
+    /// Returns the type id of the response.
+    pub fn response_type_id(&self) -> TypeId {
+        self.response_type_id
+    }
+
+    /// Returns the type name of the response.
+    pub fn response_type_name(&self) -> &'static str {
+        self.response_type_name
+    }
+}
+
+impl std::fmt::Debug for HandlerInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandlerInfo")
+            .field("request_type_name", &self.request_type_name)
+            .field("response_type_id", &self.response_type_id)
+            .field("response_type_name", &self.response_type_name)
+            .finish()
+    }
+}
+
+/// Handlers are described by their request and response types,
+/// which determine how type-erased requests are sent to them.
+impl PartialEq for HandlerInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.request_type_name == other.request_type_name
+            && self.response_type_id == other.response_type_id
+    }
+}
+
+impl Eq for HandlerInfo {}
+
+impl Mediator {
+    /// Returns the description of the handler registered for the request.
+    pub fn handler_info<TRequest: 'static>(&self) -> Option<&HandlerInfo> {
+        self.handler_infos.get(&TypeId::of::<TRequest>())
+    }
+
+    /// Returns the type id of the response of the handler registered for the request.
+    pub fn response_type_of<TRequest: 'static>(&self) -> Option<TypeId> {
+        self.handler_info::<TRequest>()
+            .map(HandlerInfo::response_type_id)
+    }
+
+    /// Returns the type names of the requests with a registered handler, sorted by name.
+    pub fn registered_requests(&self) -> Vec<&'static str> {
+        request_type_names(&self.handler_infos)
+    }
+
+    /// Send a type-erased request to the handler registered for the request type
+    /// with the given id, e.g. for a generic message queue holding requests of many types.
+    ///
+    /// The request goes through the whole pipeline as with `send`, the response being boxed
+    /// by an adapter stored when the handler was registered. Returns
+    /// `MediatorError::HandlerNotRegisteredError` if no handler is registered for the type id,
+    /// and `MediatorError::RequestTypeMismatchError` if the request fails to downcast to the
+    /// request type, i.e. is not of the type with the given id. The response has to be
+    /// downcast by the caller.
+    pub async fn send_any(
+        &mut self,
+        type_id: TypeId,
+        request: Box<dyn Any + Send>,
+    ) -> Result<Box<dyn Any + Send>> {
+        let send_any = match self.handler_infos.get(&type_id) {
+            Some(info) => info.send_any,
+            None => {
+                return Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                    format!("{:?}", type_id).into(),
+                )))
+            }
+        };
+        send_any(self, request).await
+    }
+}
+
+/// Returns the request type names of the handler infos, sorted by name.
+pub(crate) fn request_type_names(infos: &HashMap<TypeId, HandlerInfo>) -> Vec<&'static str> {
+    let mut names: Vec<_> = infos.values().map(HandlerInfo::request_type_name).collect();
+    names.sort_unstable();
+    names
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Request;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug)]
+    pub struct Echo;
+
+    impl Request<String> for Echo {}
+
+    #[test]
+    fn test_mediator_response_type_of() {
+        let mut m = Mediator::new();
+        assert_eq!(m.response_type_of::<Ping>(), None);
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert_eq!(m.response_type_of::<Ping>(), Some(TypeId::of::<String>()));
+        m.unregister_handler::<Ping, String>();
+        assert_eq!(m.response_type_of::<Ping>(), None);
+    }
+
+    #[test]
+    fn test_mediator_handler_info() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        let info = m.handler_info::<Ping>().unwrap();
+        assert_eq!(info.request_type_name(), type_name::<Ping>());
+        assert_eq!(info.response_type_name(), type_name::<String>());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_any() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        let response = m
+            .send_any(TypeId::of::<Ping>(), Box::new(Ping))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.downcast_ref::<String>().map(String::as_str),
+            Some("pong!")
+        );
+        let err = m
+            .send_any(TypeId::of::<Ping>(), Box::new(Echo))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::RequestTypeMismatchError)
+        );
+        let err = m
+            .send_any(TypeId::of::<Echo>(), Box::new(Echo))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                format!("{:?}", TypeId::of::<Echo>()).into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_mediator_registered_requests() {
+        let mut m = Mediator::new();
+        assert!(m.registered_requests().is_empty());
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) })
+            .register_handler_fn(|_: Echo| async { Ok(String::from("echo")) });
+        let mut expected = vec![type_name::<Ping>(), type_name::<Echo>()];
+        expected.sort_unstable();
+        assert_eq!(m.registered_requests(), expected);
+    }
+}
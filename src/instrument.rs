@@ -0,0 +1,86 @@
+use std::{future::Future, time::Instant};
+use tracing::Instrument;
+
+use crate::Result;
+
+/// Runs the future in a span recording the request type, then emits an event with its outcome.
+pub(crate) async fn instrument<TRequest, TResponse>(
+    future: impl Future<Output = Result<TResponse>>,
+) -> Result<TResponse> {
+    let span = tracing::debug_span!("send", request = std::any::type_name::<TRequest>());
+    let start = Instant::now();
+    let result = future.instrument(span.clone()).await;
+    let elapsed = start.elapsed();
+    match &result {
+        Ok(_) => tracing::debug!(parent: &span, ?elapsed, "request handled"),
+        Err(error) => tracing::error!(parent: &span, ?elapsed, %error, "request failed"),
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Mediator, Request};
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    /// Records the fields of every span and event.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            span.record(&mut self.clone());
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_is_traced() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+        let mut m = Mediator::new();
+        assert!(m.send(Ping).await.is_err());
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert!(m.send(Ping).await.is_ok());
+
+        let records = recorder.0.lock().unwrap();
+        let request = format!("request={:?}", std::any::type_name::<Ping>());
+        assert_eq!(records.iter().filter(|r| **r == request).count(), 2);
+        assert!(records.contains(&String::from("message=request failed")));
+        assert!(records.contains(&String::from("message=request handled")));
+    }
+}
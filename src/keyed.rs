@@ -0,0 +1,234 @@
+use futures::future::{join_all, BoxFuture};
+use std::{any::TypeId, collections::HashMap};
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result};
+
+pub(crate) type KeyedHandlers<TRequest, TResponse> =
+    HashMap<String, Box<dyn RequestHandler<TRequest, TResponse>>>;
+
+/// Calls `on_shutdown` on every handler registered under a key for the request.
+fn shutdown<TRequest, TResponse>(mediator: &mut Mediator) -> BoxFuture<'_, ()>
+where
+    TRequest: Request<TResponse>,
+    TResponse: 'static,
+{
+    Box::pin(async move {
+        if let Ok(handlers) = mediator
+            .keyed_handlers
+            .get_mut::<(TRequest, TResponse), KeyedHandlers<TRequest, TResponse>>()
+        {
+            for handler in handlers.values_mut() {
+                handler.on_shutdown().await;
+            }
+        }
+    })
+}
+
+impl Mediator {
+    /// Registers a request handler under a key, so requests of the same type can be
+    /// handled by different handlers.
+    ///
+    /// Keyed handlers are separate from the handler registered with `register_handler`.
+    /// Registering another handler under the same key replaces the previous one.
+    /// A request sent with several response types has keyed handlers per response type.
+    pub fn register_keyed_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        key: &str,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        let handler: Box<dyn RequestHandler<TRequest, TResponse>> = Box::new(handler);
+        match self
+            .keyed_handlers
+            .get_mut::<(TRequest, TResponse), KeyedHandlers<TRequest, TResponse>>()
+        {
+            Ok(handlers) => {
+                handlers.insert(key.to_string(), handler);
+            }
+            Err(_) => self
+                .keyed_handlers
+                .set::<(TRequest, TResponse), KeyedHandlers<TRequest, TResponse>>(HashMap::from([
+                    (key.to_string(), handler),
+                ])),
+        }
+        self.keyed_shutdowns.insert(
+            (TypeId::of::<TRequest>(), TypeId::of::<TResponse>()),
+            shutdown::<TRequest, TResponse>,
+        );
+        self
+    }
+
+    /// Send a request to the request handler registered under the given key.
+    ///
+    /// Keyed handlers are dispatched without the pipeline behaviors and processors.
+    pub async fn send_keyed<TRequest, TResponse>(
+        &mut self,
+        key: &str,
+        request: TRequest,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handlers = match self
+            .keyed_handlers
+            .get_mut::<(TRequest, TResponse), KeyedHandlers<TRequest, TResponse>>()
+        {
+            Ok(handlers) => handlers,
+            Err(e) => return Err(Box::new(e)),
+        };
+        match handlers.get_mut(key) {
+            Some(handler) => handler.handle(request).await,
+            None => Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TRequest>().into(),
+            ))),
+        }
+    }
+
+    /// Send a copy of the request to every request handler registered under a key for it,
+    /// concurrently, and returns their responses in the order of the keys.
+    ///
+    /// Returns no response if no keyed handler is registered for the request.
+    pub async fn send_all<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> Vec<Result<TResponse>>
+    where
+        TRequest: Request<TResponse> + Clone,
+        TResponse: 'static,
+    {
+        let handlers = match self
+            .keyed_handlers
+            .get_mut::<(TRequest, TResponse), KeyedHandlers<TRequest, TResponse>>()
+        {
+            Ok(handlers) => handlers,
+            Err(_) => return Vec::new(),
+        };
+        let mut handlers: Vec<_> = handlers.iter_mut().collect();
+        handlers.sort_by_key(|(key, _)| *key);
+        join_all(
+            handlers
+                .into_iter()
+                .map(|(_, handler)| handler.handle(request.clone())),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct Convert(f64);
+
+    impl Request<f64> for Convert {}
+
+    #[derive(Debug)]
+    pub struct CelsiusHandler;
+
+    #[async_trait::async_trait]
+    impl RequestHandler<Convert, f64> for CelsiusHandler {
+        async fn handle(&mut self, request: Convert) -> Result<f64> {
+            Ok((request.0 - 32.0) / 1.8)
+        }
+    }
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Convert| async move { Ok(request.0) })
+            .register_keyed_handler("celsius", CelsiusHandler);
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_keyed() {
+        let mut m = mediator();
+        assert_eq!(
+            m.send_keyed("celsius", Convert(212.0)).await.unwrap(),
+            100.0
+        );
+        assert_eq!(m.send(Convert(212.0)).await.unwrap(), 212.0);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_all() {
+        #[derive(Debug)]
+        pub struct KelvinHandler;
+
+        #[async_trait::async_trait]
+        impl RequestHandler<Convert, f64> for KelvinHandler {
+            async fn handle(&mut self, request: Convert) -> Result<f64> {
+                Ok((request.0 - 32.0) / 1.8 + 273.15)
+            }
+        }
+
+        assert!(Mediator::new().send_all(Convert(212.0)).await.is_empty());
+        let mut m = mediator();
+        m.register_keyed_handler("kelvin", KelvinHandler);
+        let responses: Vec<_> = m
+            .send_all(Convert(212.0))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(responses, vec![100.0, 373.15]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_keyed_no_handler_registered() {
+        let mut m = mediator();
+        let err = m.send_keyed("kelvin", Convert(212.0)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Convert>().into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_keyed_handler_several_response_types() {
+        #[derive(Debug)]
+        pub struct Format(f64);
+
+        impl Request<f64> for Format {}
+
+        impl Request<String> for Format {}
+
+        #[derive(Debug)]
+        pub struct FormatHandler;
+
+        #[async_trait::async_trait]
+        impl RequestHandler<Format, f64> for FormatHandler {
+            async fn handle(&mut self, request: Format) -> Result<f64> {
+                Ok(request.0)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl RequestHandler<Format, String> for FormatHandler {
+            async fn handle(&mut self, request: Format) -> Result<String> {
+                Ok(request.0.to_string())
+            }
+        }
+
+        let mut m = Mediator::new();
+        m.register_keyed_handler::<Format, _, f64>("format", FormatHandler)
+            .register_keyed_handler::<Format, _, String>("format", FormatHandler);
+        assert_eq!(
+            m.send_keyed::<_, f64>("format", Format(1.5)).await.unwrap(),
+            1.5
+        );
+        assert_eq!(
+            m.send_keyed::<_, String>("format", Format(1.5))
+                .await
+                .unwrap(),
+            "1.5"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use async_trait::async_trait;
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{AnyNext, GlobalPipelineBehavior, Mediator, Request, Result};
+
+/// The number of sub-buckets per power of two, giving a relative precision of about 3%.
+const SUB_BUCKETS: usize = 32;
+const SUB_BUCKET_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+/// The number of buckets covering every duration in nanoseconds representable as a `u64`.
+const BUCKETS: usize = (64 - SUB_BUCKET_BITS as usize + 1) * SUB_BUCKETS;
+
+/// A histogram of durations in nanoseconds, with logarithmic buckets split into linear
+/// sub-buckets, as in an HDR histogram.
+#[derive(Debug, Clone)]
+struct Histogram {
+    counts: Box<[u64]>,
+    count: u64,
+    max: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Histogram {
+            counts: vec![0; BUCKETS].into_boxed_slice(),
+            count: 0,
+            max: 0,
+        }
+    }
+
+    fn index(value: u64) -> usize {
+        if value < SUB_BUCKETS as u64 {
+            return value as usize;
+        }
+        let shift = 63 - value.leading_zeros() - SUB_BUCKET_BITS;
+        let sub_bucket = (value >> shift) as usize - SUB_BUCKETS;
+        (shift as usize + 1) * SUB_BUCKETS + sub_bucket
+    }
+
+    /// Returns the highest value counted in the bucket.
+    fn highest_value(index: usize) -> u64 {
+        if index < SUB_BUCKETS {
+            return index as u64;
+        }
+        let shift = (index / SUB_BUCKETS - 1) as u32;
+        let lowest = ((index % SUB_BUCKETS + SUB_BUCKETS) as u64) << shift;
+        lowest + ((1u64 << shift) - 1)
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        let value = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[Histogram::index(value)] += 1;
+        self.count += 1;
+        self.max = self.max.max(value);
+    }
+
+    fn percentile(&self, percentile: f64) -> Duration {
+        let rank = ((percentile / 100.0 * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_nanos(Histogram::highest_value(index).min(self.max));
+            }
+        }
+        Duration::from_nanos(self.max)
+    }
+}
+
+/// The latency percentiles of a request type.
+///
+/// Percentiles are rounded up to the highest duration of their bucket,
+/// so they overestimate the actual latency by at most about 3%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// The number of requests handled.
+    pub count: u64,
+    /// The median latency.
+    pub p50: Duration,
+    /// The 95th percentile latency.
+    pub p95: Duration,
+    /// The 99th percentile latency.
+    pub p99: Duration,
+    /// The highest latency.
+    pub max: Duration,
+}
+
+/// A global pipeline behavior recording the latency of every request into a histogram
+/// per request type, whose percentiles are returned by `Mediator::latency_stats`.
+///
+/// Recording a latency takes a lock and increments a counter, without allocating.
+/// Each histogram uses a fixed 15 KiB, whatever the number of requests, covering every
+/// duration with a relative precision of about 3%. Clones of the behavior share the same
+/// histograms.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyBehavior {
+    histograms: Arc<Mutex<HashMap<&'static str, Histogram>>>,
+}
+
+impl LatencyBehavior {
+    /// Creates a new latency behavior.
+    pub fn new() -> Self {
+        LatencyBehavior::default()
+    }
+
+    /// Returns the latency percentiles of the request type with the given name,
+    /// or `None` if no request of the type was handled.
+    pub fn stats(&self, request_type_name: &str) -> Option<LatencyStats> {
+        let histograms = self.histograms.lock().unwrap();
+        let histogram = histograms.get(request_type_name)?;
+        Some(LatencyStats {
+            count: histogram.count,
+            p50: histogram.percentile(50.0),
+            p95: histogram.percentile(95.0),
+            p99: histogram.percentile(99.0),
+            max: Duration::from_nanos(histogram.max),
+        })
+    }
+
+    /// Clears the recorded latencies, e.g. to only keep those of the last period.
+    pub fn reset(&self) {
+        self.histograms.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl GlobalPipelineBehavior for LatencyBehavior {
+    async fn handle(
+        &mut self,
+        request: Box<dyn Any + Send>,
+        mut next: AnyNext<'_>,
+    ) -> Result<Box<dyn Any + Send>> {
+        let start = Instant::now();
+        let response = next.run(request).await;
+        let elapsed = start.elapsed();
+        self.histograms
+            .lock()
+            .unwrap()
+            .entry(next.request_type_name())
+            .or_insert_with(Histogram::new)
+            .record(elapsed);
+        response
+    }
+}
+
+impl Mediator {
+    /// Registers a latency behavior as a global pipeline behavior,
+    /// and keeps it to return the latencies with `latency_stats`.
+    pub fn register_latency_behavior(&mut self, behavior: LatencyBehavior) -> &mut Self {
+        self.latency = Some(behavior.clone());
+        self.register_behavior(behavior)
+    }
+
+    /// Returns the latency percentiles of the request, recorded by the latency behavior.
+    ///
+    /// Returns `None` if no latency behavior is registered, or if no request was handled.
+    pub fn latency_stats<TRequest, TResponse>(&self) -> Option<LatencyStats>
+    where
+        TRequest: Request<TResponse>,
+    {
+        self.latency
+            .as_ref()?
+            .stats(std::any::type_name::<TRequest>())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Sleep(u64);
+
+    impl Request<()> for Sleep {}
+
+    #[tokio::test]
+    async fn test_mediator_latency_stats() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Sleep| async move {
+            tokio::time::sleep(Duration::from_millis(request.0)).await;
+            Ok(())
+        })
+        .register_latency_behavior(LatencyBehavior::new());
+        assert_eq!(m.latency_stats::<Sleep, ()>(), None);
+        for _ in 0..9 {
+            m.send(Sleep(0)).await.unwrap();
+        }
+        m.send(Sleep(20)).await.unwrap();
+        let stats = m.latency_stats::<Sleep, ()>().unwrap();
+        assert_eq!(stats.count, 10);
+        assert!(stats.p50 < Duration::from_millis(20));
+        assert!(stats.p99 >= Duration::from_millis(20));
+        assert_eq!(stats.p99, stats.max);
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = Histogram::new();
+        for nanos in 1..=1000 {
+            histogram.record(Duration::from_nanos(nanos));
+        }
+        for (percentile, expected) in [(50.0, 500), (95.0, 950), (99.0, 990)] {
+            let nanos = histogram.percentile(percentile).as_nanos() as f64;
+            assert!((nanos - expected as f64).abs() / expected as f64 <= 0.035);
+        }
+        assert_eq!(histogram.percentile(100.0), Duration::from_nanos(1000));
+        histogram.record(Duration::MAX);
+        assert_eq!(Histogram::index(u64::MAX), BUCKETS - 1);
+        assert_eq!(Histogram::highest_value(BUCKETS - 1), u64::MAX);
+    }
+}
@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use std::{
+    error::Error,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Mediator, MediatorError, Request, RequestContext, RequestHandler, Result};
+
+enum LazyState<F, Fut, TRequestHandler> {
+    Pending(Option<F>),
+    /// The initialization started by a request, resumed by the next one if it was cancelled.
+    /// The mutex makes the handler `Sync` without requiring the future to be, it is never locked.
+    Initializing(Mutex<Pin<Box<Fut>>>),
+    Ready(TRequestHandler),
+    Failed(Arc<dyn Error + Send + Sync>),
+}
+
+/// Builds the request handler on its first request.
+struct LazyHandler<F, Fut, TRequestHandler, TRequest, TResponse> {
+    state: LazyState<F, Fut, TRequestHandler>,
+    _marker: PhantomData<fn(TRequest) -> TResponse>,
+}
+
+impl<F, Fut, TRequestHandler, TRequest, TResponse>
+    LazyHandler<F, Fut, TRequestHandler, TRequest, TResponse>
+where
+    F: FnOnce() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<TRequestHandler>> + Send,
+    TRequestHandler: RequestHandler<TRequest, TResponse>,
+    TRequest: Request<TResponse>,
+{
+    async fn handler(&mut self) -> Result<&mut TRequestHandler> {
+        if let LazyState::Pending(factory) = &mut self.state {
+            let factory = factory
+                .take()
+                .expect("the factory is only taken to start the initialization");
+            self.state = LazyState::Initializing(Mutex::new(Box::pin(factory())));
+        }
+        if let LazyState::Initializing(init) = &mut self.state {
+            self.state = match init.get_mut().unwrap().as_mut().await {
+                Ok(handler) => LazyState::Ready(handler),
+                Err(e) => LazyState::Failed(Arc::from(e)),
+            };
+        }
+        match &mut self.state {
+            LazyState::Ready(handler) => Ok(handler),
+            LazyState::Failed(e) => Err(Box::new(MediatorError::HandlerInitFailedError(e.clone()))),
+            LazyState::Pending(_) | LazyState::Initializing(_) => {
+                unreachable!("the handler is initialized")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<F, Fut, TRequestHandler, TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for LazyHandler<F, Fut, TRequestHandler, TRequest, TResponse>
+where
+    F: FnOnce() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<TRequestHandler>> + Send,
+    TRequestHandler: RequestHandler<TRequest, TResponse>,
+    TRequest: Request<TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        self.handler().await?.handle(request).await
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.handler()
+            .await?
+            .handle_with_context(request, context)
+            .await
+    }
+
+    /// Shuts the handler down if it was built, without building it otherwise.
+    async fn on_shutdown(&mut self) {
+        if let LazyState::Ready(handler) = &mut self.state {
+            handler.on_shutdown().await;
+        }
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler built by the factory on its first request,
+    /// e.g. for a handler opening a connection pool.
+    ///
+    /// The handler is then reused by the next requests. If the factory fails, every request
+    /// fails with `MediatorError::HandlerInitFailedError`, holding the error of the factory.
+    /// If the request building the handler is cancelled, the next request resumes building it.
+    pub fn register_lazy_handler<TRequest, TRequestHandler, TResponse, F, Fut>(
+        &mut self,
+        factory: F,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+        F: FnOnce() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<TRequestHandler>> + Send + 'static,
+    {
+        self.register_handler(LazyHandler {
+            state: LazyState::Pending(Some(factory)),
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug)]
+    pub struct Query;
+
+    impl Request<usize> for Query {}
+
+    #[derive(Debug)]
+    pub struct PoolHandler(usize);
+
+    #[async_trait]
+    impl RequestHandler<Query, usize> for PoolHandler {
+        async fn handle(&mut self, _request: Query) -> Result<usize> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_lazy_handler() {
+        let built = Arc::new(AtomicUsize::new(0));
+        let factory_built = built.clone();
+        let mut m = Mediator::new();
+        m.register_lazy_handler(move || async move {
+            Ok(PoolHandler(
+                factory_built.fetch_add(1, Ordering::SeqCst) + 1,
+            ))
+        });
+        assert_eq!(built.load(Ordering::SeqCst), 0);
+        assert_eq!(m.send(Query).await.unwrap(), 1);
+        assert_eq!(m.send(Query).await.unwrap(), 1);
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_lazy_handler_cancelled_init() {
+        let built = Arc::new(AtomicUsize::new(0));
+        let factory_built = built.clone();
+        let mut m = Mediator::new();
+        m.register_lazy_handler(move || async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(PoolHandler(
+                factory_built.fetch_add(1, Ordering::SeqCst) + 1,
+            ))
+        });
+        let cancelled =
+            tokio::time::timeout(std::time::Duration::from_millis(5), m.send(Query)).await;
+        assert!(cancelled.is_err());
+        assert_eq!(m.send(Query).await.unwrap(), 1);
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_lazy_handler_init_failed() {
+        let mut m = Mediator::new();
+        m.register_lazy_handler(|| async { Err::<PoolHandler, _>("connection refused".into()) });
+        for _ in 0..2 {
+            let err = m.send(Query).await.unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Handler initialization failed: connection refused"
+            );
+        }
+    }
+}
@@ -31,6 +31,33 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Features
+//!
+//! - `async-std`: selects the async-std timer for the `retry` and `timeout` features.
+//! - `blocking`: enables `Mediator::blocking_send`, to send requests from synchronous code.
+//! - `derive`: enables `#[derive(Request)]`, e.g. `#[derive(Request)] #[response(String)] struct Ping;`,
+//!   and the `#[handler]` attribute, implementing `RequestHandler` from an `async fn handle`.
+//! - `log`: enables the `LoggingBehavior`, logging requests and responses with the `log` crate.
+//! - `rand`: enables `Mediator::register_weighted_handler`, picking a handler at random
+//!   in proportion to its weight.
+//! - `retry`: enables the `RetryBehavior`. Its timer is that of the `tokio` or `async-std`
+//!   feature, or a thread spawned per timer when neither is enabled.
+//! - `serde`: enables `Mediator::send_serialized`, to send requests serialized as JSON,
+//!   and implements `Serialize` and `Deserialize` for the `ValidationFailure`.
+//! - `test-util`: enables the `MockMediator`, to stub responses in tests.
+//! - `timeout`: enables `Mediator::send_with_timeout`, `Mediator::register_handler_with_timeout`
+//!   and the `DeadlineBehavior`. Their timer is that of the `tokio` or `async-std` feature,
+//!   or a thread spawned per timer when neither is enabled.
+//! - `tokio`: selects the tokio timer for the `retry` and `timeout` features, and enables the
+//!   `ConcurrencyLimitBehavior` and the `SerializedHandler`.
+//! - `tokio-util`: enables `Mediator::send_with_cancellation`, based on the tokio-util
+//!   `CancellationToken`.
+//! - `tracing`: emits a `tracing` span around every `send`, and an event when it fails.
+//! - `uuid`: enables the `RequestIdBehavior`, giving every request a random `RequestId`.
+//!
+//! The core dispatch does not depend on any async runtime, only the `retry` and `timeout`
+//! helpers need a timer.
 
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
@@ -38,39 +65,291 @@
 
 use async_trait::async_trait;
 use std::fmt::Debug;
-use std::{
-    any::{Any, TypeId},
-    collections::HashMap,
-    error::Error,
-};
+use std::future::Future;
+use std::{any::TypeId, collections::HashMap, error::Error};
 
+mod any_map;
+mod batch;
+mod behavior;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod builder;
+mod caching;
+#[cfg(feature = "tokio-util")]
+mod cancellation;
+mod chain;
+mod circuit_breaker;
+mod collect;
+#[cfg(feature = "tokio")]
+mod concurrency;
+mod concurrent;
+mod conditional;
+mod context;
+mod deadline;
+mod decorate;
+mod dedup;
+mod drain;
 mod error;
-pub use self::error::MediatorError;
+mod error_hook;
+mod ext;
+mod feature_flag;
+mod handler_fn;
+mod idempotency;
+mod info;
+#[cfg(feature = "tracing")]
+mod instrument;
+mod keyed;
+mod latency;
+mod lazy;
+mod local;
+#[cfg(feature = "log")]
+mod logging;
+mod mapped;
+mod metrics;
+#[cfg(feature = "test-util")]
+mod mock;
+mod named;
+mod notification;
+mod one_of;
+mod open;
+mod panic;
+mod parts;
+mod processor;
+mod progress;
+mod read;
+mod recording;
+mod registrable;
+#[cfg(feature = "uuid")]
+mod request_id;
+#[cfg(feature = "retry")]
+mod retry;
+#[cfg(any(feature = "retry", feature = "timeout"))]
+mod runtime;
+mod scope;
+mod send_future;
+#[cfg(feature = "serde")]
+mod serialized;
+#[cfg(feature = "tokio")]
+mod serialized_handler;
+mod services;
+mod shared;
+mod shared_handler;
+mod shutdown;
+mod stream;
+mod sync_handler;
+mod timed;
+#[cfg(feature = "timeout")]
+mod timeout;
+mod transaction;
+mod unboxed;
+mod validation;
+mod versioning;
+#[cfg(feature = "rand")]
+mod weighted;
+pub use self::any_map::AnyMap;
+pub use self::batch::BatchHandler;
+pub use self::behavior::{AnyNext, GlobalPipelineBehavior, Next, PipelineBehavior};
+pub use self::builder::MediatorBuilder;
+pub use self::caching::{Cacheable, CachingBehavior};
+pub use self::chain::ChainableHandler;
+pub use self::circuit_breaker::CircuitBreakerBehavior;
+pub use self::collect::CollectingNotificationHandler;
+#[cfg(feature = "tokio")]
+pub use self::concurrency::ConcurrencyLimitBehavior;
+pub use self::concurrent::ConcurrentMediator;
+pub use self::context::RequestContext;
+pub use self::deadline::Deadline;
+#[cfg(feature = "timeout")]
+pub use self::deadline::DeadlineBehavior;
+pub use self::decorate::BoxedHandler;
+pub use self::dedup::DeduplicationBehavior;
+pub use self::error::{MediatorError, SendError};
+pub use self::ext::MediatorExt;
+pub use self::feature_flag::FeatureFlagBehavior;
+pub use self::idempotency::{
+    DuplicatePolicy, HasIdempotencyKey, IdempotencyBehavior, IdempotencyClaim, IdempotencyStore,
+    InMemoryIdempotencyStore,
+};
+pub use self::info::HandlerInfo;
+pub use self::latency::{LatencyBehavior, LatencyStats};
+pub use self::local::{LocalMediator, LocalRequestHandler};
+#[cfg(feature = "log")]
+pub use self::logging::LoggingBehavior;
+pub use self::metrics::MetricsRecorder;
+#[cfg(feature = "test-util")]
+pub use self::mock::{Expectation, MockMediator};
+pub use self::named::FallbackHandler;
+pub use self::notification::{Notification, NotificationHandler};
+pub use self::one_of::OneOf;
+pub use self::parts::MediatorParts;
+pub use self::processor::{RequestPostProcessor, RequestPreProcessor};
+pub use self::progress::{ProgressReporter, ProgressRequest, ProgressStream};
+pub use self::read::ReadHandler;
+pub use self::recording::RecordingBehavior;
+pub use self::registrable::Registrable;
+#[cfg(feature = "uuid")]
+pub use self::request_id::{RequestId, RequestIdBehavior};
+#[cfg(feature = "retry")]
+pub use self::retry::RetryBehavior;
+pub use self::scope::ScopedMediator;
+pub use self::send_future::SendFuture;
+#[cfg(feature = "serde")]
+pub use self::serialized::{Deserializer, SerializableRequest};
+#[cfg(feature = "tokio")]
+pub use self::serialized_handler::SerializedHandler;
+pub use self::services::ServiceProvider;
+pub use self::shared::SharedMediator;
+pub use self::stream::{ResponseStream, StreamRequest, StreamRequestHandler};
+pub use self::sync_handler::SyncRequestHandler;
+pub use self::transaction::RegistrationBatch;
+pub use self::unboxed::UnboxedRequestHandler;
+pub use self::validation::{ValidationBehavior, ValidationFailure, Validator};
+pub use self::versioning::{Versioned, VersioningBehavior};
+#[cfg(feature = "derive")]
+pub use brazier_derive::{handler, Request};
+
+/// Items used by the code generated by the derive macros, not part of the public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait::async_trait;
+}
 
 /// The result type returned by the RequestHandler.
-pub type Result<T> = core::result::Result<T, Box<dyn Error>>;
+pub type Result<T> = core::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 /// The request trait.
-pub trait Request<TResponse>: 'static {}
+///
+/// The response type is a parameter rather than an associated type, so a request type can be
+/// handled with several response types. When a request implements `Request` for a single
+/// response type, which is the common case, the response type of `send` is inferred
+/// and no turbofish is needed:
+///
+/// ```rust
+/// # use brazier::*;
+/// pub struct Ping;
+///
+/// impl Request<String> for Ping {}
+///
+/// # async fn run(mut mediator: Mediator) -> Result<()> {
+/// let response = mediator.send(Ping).await?;
+/// assert_eq!(response, "pong!");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A request implementing `Request` for several response types, by design or by mistake,
+/// makes every `send` of it ambiguous, so the response type has to be named:
+///
+/// ```compile_fail
+/// # use brazier::*;
+/// pub struct Ping;
+///
+/// impl Request<String> for Ping {}
+/// impl Request<u64> for Ping {}
+///
+/// # async fn run(mut mediator: Mediator) -> Result<()> {
+/// let response = mediator.send(Ping).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Making the response an associated type would rule this out, at the cost of handling a
+/// request type with a single response type only. The response stays a parameter instead,
+/// and the `derive` feature offers `#[derive(Request)]`, which implements the trait for the
+/// single response type given by its `#[response(...)]` attribute and rejects a second one.
+pub trait Request<TResponse>: Send + Sync + 'static {}
 
 /// The request handler trait. The handler is responsible for handling the request.
+///
+/// The handler and the future it returns are `Send`, so requests can be sent from tasks
+/// spawned on a multithreaded runtime. See the `LocalRequestHandler` for handlers that are not.
 #[async_trait]
-pub trait RequestHandler<TRequest, TResponse>
+pub trait RequestHandler<TRequest, TResponse>: Send + Sync
 where
     TRequest: Request<TResponse>,
 {
     /// The method that handles the request.
     async fn handle(&mut self, request: TRequest) -> Result<TResponse>;
+
+    /// The method that handles the request sent with a context.
+    /// Defaults to `handle`, ignoring the context.
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        _context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.handle(request).await
+    }
+
+    /// The method called by `Mediator::shutdown` before the handler is dropped,
+    /// e.g. to flush a buffer or close a connection. Defaults to doing nothing.
+    async fn on_shutdown(&mut self) {}
 }
 
 /// The mediator trait.
-#[derive(Debug)]
-pub struct Mediator(TypeMap);
+pub struct Mediator {
+    handlers: AnyMap,
+    handler_infos: HashMap<TypeId, HandlerInfo>,
+    read_handlers: AnyMap,
+    behaviors: AnyMap,
+    global_behaviors: behavior::GlobalBehaviors,
+    pre_processors: AnyMap,
+    post_processors: AnyMap,
+    notification_handlers: AnyMap,
+    collecting_handlers: AnyMap,
+    stream_handlers: AnyMap,
+    batch_handlers: AnyMap,
+    chainable_handlers: AnyMap,
+    sync_handlers: AnyMap,
+    keyed_handlers: AnyMap,
+    keyed_shutdowns: HashMap<(TypeId, TypeId), info::Shutdown>,
+    conditional_handlers: AnyMap,
+    #[cfg(feature = "rand")]
+    weighted_handlers: AnyMap,
+    named_handlers: named::NamedHandlers,
+    #[cfg(feature = "serde")]
+    deserializers: serialized::Deserializers,
+    #[cfg(feature = "timeout")]
+    default_timeouts: timeout::DefaultTimeouts,
+    services: std::sync::Arc<ServiceProvider>,
+    latency: Option<LatencyBehavior>,
+    recorder: metrics::Recorder,
+    error_hook: error_hook::ErrorHook,
+}
 
 impl Mediator {
     /// Creates a new mediator.
     pub fn new() -> Self {
-        Mediator(TypeMap::new())
+        Mediator {
+            handlers: AnyMap::new(),
+            handler_infos: HashMap::new(),
+            read_handlers: AnyMap::new(),
+            behaviors: AnyMap::new(),
+            global_behaviors: behavior::GlobalBehaviors::default(),
+            pre_processors: AnyMap::new(),
+            post_processors: AnyMap::new(),
+            notification_handlers: AnyMap::new(),
+            collecting_handlers: AnyMap::new(),
+            stream_handlers: AnyMap::new(),
+            batch_handlers: AnyMap::new(),
+            chainable_handlers: AnyMap::new(),
+            sync_handlers: AnyMap::new(),
+            keyed_handlers: AnyMap::new(),
+            keyed_shutdowns: HashMap::new(),
+            conditional_handlers: AnyMap::new(),
+            #[cfg(feature = "rand")]
+            weighted_handlers: AnyMap::new(),
+            named_handlers: named::NamedHandlers::default(),
+            #[cfg(feature = "serde")]
+            deserializers: serialized::Deserializers::default(),
+            #[cfg(feature = "timeout")]
+            default_timeouts: timeout::DefaultTimeouts::default(),
+            services: std::sync::Arc::default(),
+            latency: None,
+            recorder: metrics::Recorder::default(),
+            error_hook: error_hook::ErrorHook::default(),
+        }
     }
 
     /// Registers a request handler.
@@ -81,45 +360,367 @@ impl Mediator {
     where
         TRequest: Request<TResponse>,
         TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
-        TResponse: 'static,
+        TResponse: Send + 'static,
     {
-        self.0
+        self.handlers
             .set::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>(Box::new(handler));
+        self.chainable_handlers
+            .remove::<TRequest, chain::ChainableHandlerSlot<TRequest, TResponse>>();
+        self.conditional_handlers
+            .remove::<TRequest, conditional::SharedConditionalHandlers<TRequest, TResponse>>();
+        #[cfg(feature = "rand")]
+        self.weighted_handlers
+            .remove::<TRequest, weighted::SharedWeightedHandlers<TRequest, TResponse>>();
+        #[cfg(feature = "timeout")]
+        self.default_timeouts.remove::<TRequest>();
+        self.handler_infos.insert(
+            TypeId::of::<TRequest>(),
+            HandlerInfo::of::<TRequest, TResponse>(),
+        );
         self
     }
 
-    /// Send a request to the mediator.
-    pub async fn send<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    /// Registers a request handler, and returns the request handler it replaces, if any.
+    pub fn replace_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> Option<Box<dyn RequestHandler<TRequest, TResponse>>>
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        let previous = self
+            .handlers
+            .remove::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>();
+        self.register_handler(handler);
+        previous
+    }
+
+    /// Registers a request handler, unless a handler is already registered for the request.
+    pub fn try_register_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> core::result::Result<&mut Self, MediatorError>
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        if self.handler_infos.contains_key(&TypeId::of::<TRequest>()) {
+            return Err(MediatorError::HandlerAlreadyRegisteredError);
+        }
+        Ok(self.register_handler(handler))
+    }
+
+    /// Registers a request handler if there is one, e.g. for a handler behind a feature flag.
+    ///
+    /// When there is none, nothing is registered and requests fail with
+    /// `MediatorError::HandlerNotRegisteredError`. A registered handler which is
+    /// temporarily unable to handle requests can return `MediatorError::HandlerUnavailableError`.
+    pub fn register_maybe_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: Option<TRequestHandler>,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        match handler {
+            Some(handler) => self.register_handler(handler),
+            None => self,
+        }
+    }
+
+    /// Unregisters the request handler.
+    ///
+    /// Returns whether a handler was registered for the request.
+    pub fn unregister_handler<TRequest, TResponse>(&mut self) -> bool
     where
         TRequest: Request<TResponse>,
         TResponse: 'static,
     {
-        match self
-            .0
-            .get_mut::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()
-        {
-            Some(h) => h.handle(request).await,
-            None => Err(Box::new(error::MediatorError::HandlerNotRegisteredError)),
+        let removed = self
+            .handlers
+            .remove::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()
+            .is_some()
+            || self
+                .chainable_handlers
+                .remove::<TRequest, chain::ChainableHandlerSlot<TRequest, TResponse>>()
+                .is_some();
+        if removed {
+            self.conditional_handlers
+                .remove::<TRequest, conditional::SharedConditionalHandlers<TRequest, TResponse>>();
+            #[cfg(feature = "rand")]
+            self.weighted_handlers
+                .remove::<TRequest, weighted::SharedWeightedHandlers<TRequest, TResponse>>();
+            #[cfg(feature = "timeout")]
+            self.default_timeouts.remove::<TRequest>();
+            self.handler_infos.remove(&TypeId::of::<TRequest>());
         }
+        removed
     }
-}
 
-#[derive(Debug)]
-struct TypeMap(HashMap<TypeId, Box<dyn Any>>);
+    /// Returns whether a handler is registered for the request.
+    pub fn has_handler<TRequest, TResponse>(&self) -> bool
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        self.handlers
+            .get::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()
+            .is_some()
+            || self
+                .chainable_handlers
+                .get::<TRequest, chain::ChainableHandlerSlot<TRequest, TResponse>>()
+                .is_some()
+    }
 
-impl TypeMap {
-    fn new() -> Self {
-        TypeMap(HashMap::new())
+    /// Returns the number of registered request handlers.
+    pub fn handler_count(&self) -> usize {
+        self.handlers.len() + self.chainable_handlers.len()
     }
 
-    pub fn set<TKey: 'static, TValue: Any + 'static>(&mut self, value: TValue) {
-        self.0.insert(TypeId::of::<TKey>(), Box::new(value));
+    /// Send a request to the mediator.
+    ///
+    /// The returned `SendFuture` is awaited to get the response.
+    ///
+    /// The request is handled within the returned future, nothing is spawned: dropping it
+    /// before it completes cancels the request, abandoning the handler and the behaviors
+    /// at their current await point. The handlers stay registered and handle the next requests.
+    /// The only exception is a handler registered with `register_serialized_handler`, which
+    /// runs on its own task: a request it has queued is still handled once its future is
+    /// dropped, only the response is discarded.
+    ///
+    /// # Concurrency
+    ///
+    /// `send` borrows the mediator exclusively, so a mediator handles one request at a time.
+    /// Requests are handled concurrently across mediators, e.g. one mediator per task,
+    /// which share state by registering clones of the same handlers and behaviors.
+    pub fn send<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> SendFuture<impl Future<Output = Result<TResponse>> + Send + '_>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        SendFuture::new(self.send_with_context(request, RequestContext::new()))
     }
 
-    pub fn get_mut<TKey: 'static, TValue: Any + 'static>(&mut self) -> Option<&mut TValue> {
-        self.0
-            .get_mut(&TypeId::of::<TKey>())
-            .and_then(|v| v.downcast_mut::<TValue>())
+    /// Send a request to the mediator with a context,
+    /// which is available to the pipeline behaviors and the request handler.
+    ///
+    /// A context without a `Deadline` inherits the deadline of the request being handled,
+    /// when sent by a handler.
+    pub async fn send_with_context<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+        mut context: RequestContext,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        if context.get::<Deadline>().is_none() {
+            if let Some(deadline) = deadline::current() {
+                context.insert(deadline);
+            }
+        }
+        self.send_inner(request, &context, None).await
+    }
+
+    async fn send_inner<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+        elapsed: Option<&mut std::time::Duration>,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.provide_services(context);
+        let recorder = self.recorder.0.clone();
+        let error_hook = self.error_hook.0.clone();
+        let deadline = context
+            .get::<Deadline>()
+            .copied()
+            .or_else(deadline::current);
+        #[cfg(feature = "timeout")]
+        let duration = context
+            .timeout()
+            .or_else(|| self.default_timeouts.get::<TRequest>());
+        let future =
+            deadline::WithDeadline::new(deadline, self.dispatch(request, context, elapsed));
+        #[cfg(feature = "timeout")]
+        let future = timeout::limit(duration, deadline, future);
+        #[cfg(feature = "tracing")]
+        let future = instrument::instrument::<TRequest, _>(future);
+        let result = match recorder {
+            Some(recorder) => metrics::record::<TRequest, _>(&*recorder, future).await,
+            None => future.await,
+        };
+        if let (Err(e), Some(hook)) = (&result, error_hook) {
+            hook(&**e);
+        }
+        result
+    }
+
+    /// Send a request to the mediator, returning every error as a `MediatorError`,
+    /// so callers match on a single error type instead of downcasting.
+    ///
+    /// The errors of the mediator itself are returned as is, and the errors of the handler
+    /// are wrapped into `MediatorError::HandlerError`. `send` keeps returning the boxed error,
+    /// so handlers and behaviors can return any error type with `?`.
+    pub async fn send_typed<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> core::result::Result<TResponse, MediatorError>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.send(request).await.map_err(MediatorError::from)
+    }
+
+    /// Send a copy of the request to the mediator, so the caller keeps the request.
+    ///
+    /// The request is cloned on every call; prefer `send` when the request is no longer needed.
+    pub async fn send_ref<TRequest, TResponse>(&mut self, request: &TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse> + Clone,
+        TResponse: Send + 'static,
+    {
+        self.send(request.clone()).await
+    }
+
+    /// Send a command, i.e. a request without response, to the mediator.
+    pub async fn send_command<TRequest>(&mut self, request: TRequest) -> Result<()>
+    where
+        TRequest: Request<()>,
+    {
+        self.send(request).await
+    }
+
+    /// Send a request to the mediator for its side effects, discarding the response.
+    ///
+    /// The errors are still returned. Unlike `send_command`, the request can have any response,
+    /// e.g. the id of a created entity the caller does not need.
+    pub async fn fire<TRequest, TResponse>(&mut self, request: TRequest) -> Result<()>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        self.send(request).await.map(|_| ())
+    }
+
+    /// Send a request to the mediator, tolerating a missing handler.
+    ///
+    /// Returns `Ok(None)` when no handler is registered for the request,
+    /// while the errors of the handler are still returned.
+    pub async fn try_send<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> Result<Option<TResponse>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        if !self.handler_infos.contains_key(&TypeId::of::<TRequest>()) {
+            return Ok(None);
+        }
+        self.send(request).await.map(Some)
+    }
+
+    async fn dispatch<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+        elapsed: Option<&mut std::time::Duration>,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        if self.chainable_handlers.contains::<TRequest>() {
+            return chain::dispatch(self, request, elapsed).await;
+        }
+        let handler = match self
+            .handlers
+            .get_mut::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()
+        {
+            Ok(h) => h,
+            Err(e) => return Err(Box::new(e)),
+        };
+        if let Ok(pre_processors) = self
+            .pre_processors
+            .get_mut::<TRequest, processor::PreProcessors<TRequest>>()
+        {
+            for pre_processor in pre_processors.iter_mut() {
+                pre_processor.process(&request).await?;
+            }
+        }
+        let post_processors = self
+            .post_processors
+            .get_mut::<TRequest, processor::PostProcessors<TRequest, TResponse>>()
+            .ok();
+        let sent = post_processors
+            .as_ref()
+            .map(|post_processors| (post_processors.clone_request)(&request));
+        let behaviors = match self
+            .behaviors
+            .get_mut::<TRequest, behavior::Behaviors<TRequest, TResponse>>()
+            .ok()
+        {
+            Some(behaviors) => behaviors.as_mut_slice(),
+            None => &mut [],
+        };
+        let mut timed;
+        let handler: &mut dyn RequestHandler<TRequest, TResponse> = match elapsed {
+            Some(elapsed) => {
+                timed = timed::TimedHandler::new(handler.as_mut(), elapsed);
+                &mut timed
+            }
+            None => handler.as_mut(),
+        };
+        let next = Next::new(behaviors, handler, context);
+        let response = behavior::run(&mut self.global_behaviors, next, request).await?;
+        if let (Some(post_processors), Some(sent)) = (post_processors, sent) {
+            for post_processor in post_processors.post_processors.iter_mut() {
+                post_processor.process(&sent, &response).await?;
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl Default for Mediator {
+    fn default() -> Self {
+        Mediator::new()
+    }
+}
+
+/// Lists the type names of the requests with a registered handler,
+/// and the number of types registered with the other kinds of handlers.
+impl Debug for Mediator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mediator")
+            .field("handlers", &self.registered_requests())
+            .field("read_handlers", &self.read_handlers.len())
+            .field("behaviors", &self.behaviors.len())
+            .field("global_behaviors", &self.global_behaviors)
+            .field("pre_processors", &self.pre_processors.len())
+            .field("post_processors", &self.post_processors.len())
+            .field("notification_handlers", &self.notification_handlers.len())
+            .field("collecting_handlers", &self.collecting_handlers.len())
+            .field("stream_handlers", &self.stream_handlers.len())
+            .field("batch_handlers", &self.batch_handlers.len())
+            .field("sync_handlers", &self.sync_handlers.len())
+            .field("keyed_handlers", &self.keyed_handlers.len())
+            .field("named_handlers", &self.named_handlers)
+            .finish_non_exhaustive()
     }
 }
 
@@ -127,7 +728,7 @@ impl TypeMap {
 mod test {
     use super::*;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct TestRequest {}
 
     #[derive(Debug)]
@@ -153,14 +754,316 @@ mod test {
     async fn test_mediator_no_handler_registered() {
         let mut m = Mediator::new();
         match m.send(TestRequest {}).await {
-            Ok(_) => assert!(false),
+            Ok(_) => panic!("expected an error"),
             Err(err) => {
                 if let Some(e) = err.downcast_ref::<MediatorError>() {
-                    assert_eq!(e, &error::MediatorError::HandlerNotRegisteredError);
+                    assert_eq!(
+                        e,
+                        &error::MediatorError::HandlerNotRegisteredError(
+                            std::any::type_name::<TestRequest>().into()
+                        )
+                    );
+                    assert_eq!(
+                        e.to_string(),
+                        format!(
+                            "Handler not registered for `{}`",
+                            std::any::type_name::<TestRequest>()
+                        )
+                    );
                 } else {
-                    assert!(false);
+                    panic!("expected a mediator error");
                 }
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_mediator_unregister_handler() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler);
+        assert!(m.unregister_handler::<TestRequest, i64>());
+        assert!(!m.unregister_handler::<TestRequest, i64>());
+        let err = m.send(TestRequest {}).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TestRequest>().into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_reregister_handler() {
+        let mut m = Mediator::new();
+        for _ in 0..2 {
+            m.register_handler(TestRequestHandler);
+            assert_eq!(m.send(TestRequest {}).await.unwrap(), 42);
+            assert!(m.unregister_handler::<TestRequest, i64>());
+        }
+        assert!(m.send(TestRequest {}).await.is_err());
+    }
+
+    #[test]
+    fn test_mediator_has_handler() {
+        let mut m = Mediator::new();
+        assert!(!m.has_handler::<TestRequest, i64>());
+        m.register_handler(TestRequestHandler);
+        assert!(m.has_handler::<TestRequest, i64>());
+    }
+
+    #[test]
+    fn test_mediator_has_handler_other_response_type() {
+        pub struct MultiRequest;
+
+        impl Request<i64> for MultiRequest {}
+
+        impl Request<String> for MultiRequest {}
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: MultiRequest| async { Ok(42i64) });
+        assert!(m.has_handler::<MultiRequest, i64>());
+        assert!(!m.has_handler::<MultiRequest, String>());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_handler_type_mismatch() {
+        pub struct MultiRequest;
+
+        impl Request<i64> for MultiRequest {}
+
+        impl Request<String> for MultiRequest {}
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: MultiRequest| async { Ok(42i64) });
+        let err = m.send::<_, String>(MultiRequest).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerTypeMismatchError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_error_no_handler_registered() {
+        let mut m = Mediator::new();
+        match m.send(TestRequest {}).await.map_err(SendError::from) {
+            Err(SendError::Mediator(e)) => {
+                assert_eq!(
+                    e,
+                    MediatorError::HandlerNotRegisteredError(
+                        std::any::type_name::<TestRequest>().into()
+                    )
+                )
+            }
+            _ => panic!("expected a mediator error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_error_downcast_handler_error() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: TestRequest| async { Err(std::fmt::Error.into()) });
+        let err = SendError::from(m.send(TestRequest {}).await.unwrap_err());
+        match err.downcast::<std::fmt::Error>() {
+            Ok(SendError::Handler(e)) => assert_eq!(e, std::fmt::Error),
+            _ => panic!("expected a handler error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_typed() {
+        let mut m = Mediator::new();
+        assert_eq!(
+            m.send_typed(TestRequest {}).await.unwrap_err(),
+            MediatorError::HandlerNotRegisteredError(std::any::type_name::<TestRequest>().into())
+        );
+        m.register_handler_fn(|_: TestRequest| async { Err(std::fmt::Error.into()) });
+        match m.send_typed(TestRequest {}).await {
+            Err(MediatorError::HandlerError(e)) => assert!(e.is::<std::fmt::Error>()),
+            _ => panic!("expected a handler error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_default() {
+        #[derive(Debug, Default)]
+        struct App {
+            mediator: Mediator,
+        }
+
+        let mut app = App::default();
+        app.mediator.register_handler(TestRequestHandler);
+        assert_eq!(app.mediator.send(TestRequest {}).await.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_mediator_try_register_handler_twice() {
+        let mut m = Mediator::new();
+        assert!(m.try_register_handler(TestRequestHandler).is_ok());
+        assert_eq!(
+            m.try_register_handler(TestRequestHandler).err(),
+            Some(MediatorError::HandlerAlreadyRegisteredError)
+        );
+    }
+
+    #[test]
+    fn test_mediator_handler_count() {
+        pub struct OtherRequest;
+
+        impl Request<()> for OtherRequest {}
+
+        let mut m = Mediator::new();
+        assert_eq!(m.handler_count(), 0);
+        m.register_handler(TestRequestHandler);
+        m.register_handler(TestRequestHandler);
+        m.register_handler_fn(|_: OtherRequest| async { Ok(()) });
+        assert_eq!(m.handler_count(), 2);
+        m.unregister_handler::<OtherRequest, ()>();
+        assert_eq!(m.handler_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_ref() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler);
+        let request = TestRequest {};
+        assert_eq!(m.send_ref(&request).await.unwrap(), 42);
+        assert_eq!(m.send(request).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_from_spawned_task() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler);
+        let response = tokio::spawn(async move { m.send(TestRequest {}).await.unwrap() });
+        assert_eq!(response.await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_command() {
+        #[derive(Debug)]
+        pub struct TestCommand;
+
+        impl Request<()> for TestCommand {}
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: TestCommand| async { Ok(()) });
+        assert!(m.send_command(TestCommand).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_fire() {
+        let mut m = Mediator::new();
+        assert_eq!(
+            m.fire(TestRequest {})
+                .await
+                .unwrap_err()
+                .downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TestRequest>().into()
+            ))
+        );
+        m.register_handler(TestRequestHandler);
+        assert!(m.fire(TestRequest {}).await.is_ok());
+    }
+
+    #[test]
+    fn test_mediator_debug() {
+        let mut m = Mediator::new();
+        m.register_handler(TestRequestHandler);
+        let debug = format!("{:?}", m);
+        assert!(debug.starts_with(&format!(
+            "Mediator {{ handlers: [{:?}], read_handlers: 0,",
+            std::any::type_name::<TestRequest>()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_mediator_try_send() {
+        let mut m = Mediator::new();
+        assert_eq!(m.try_send(TestRequest {}).await.unwrap(), None);
+        m.register_handler(TestRequestHandler);
+        assert_eq!(m.try_send(TestRequest {}).await.unwrap(), Some(42));
+        m.register_handler_fn(|_: TestRequest| async { Err::<i64, _>("failed".into()) });
+        assert!(m.try_send(TestRequest {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_replace_handler() {
+        #[derive(Debug)]
+        pub struct OtherHandler;
+
+        #[async_trait]
+        impl RequestHandler<TestRequest, i64> for OtherHandler {
+            async fn handle(&mut self, _request: TestRequest) -> Result<i64> {
+                Ok(7)
+            }
+        }
+
+        let mut m = Mediator::new();
+        assert!(m.replace_handler(TestRequestHandler).is_none());
+        let mut previous = m.replace_handler(OtherHandler).unwrap();
+        assert_eq!(previous.handle(TestRequest {}).await.unwrap(), 42);
+        assert_eq!(m.send(TestRequest {}).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_maybe_handler() {
+        #[derive(Debug)]
+        pub struct Cache(Option<i64>);
+
+        #[async_trait]
+        impl RequestHandler<TestRequest, i64> for Cache {
+            async fn handle(&mut self, _request: TestRequest) -> Result<i64> {
+                self.0
+                    .ok_or_else(|| Box::new(MediatorError::HandlerUnavailableError) as _)
+            }
+        }
+
+        let mut m = Mediator::new();
+        m.register_maybe_handler(None::<Cache>);
+        let err = m.send(TestRequest {}).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TestRequest>().into()
+            ))
+        );
+
+        m.register_maybe_handler(Some(Cache(None)));
+        let err = m.send(TestRequest {}).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerUnavailableError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_cancelled_on_drop() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        #[derive(Debug)]
+        pub struct Work;
+
+        impl Request<()> for Work {}
+
+        let done = Arc::new(AtomicBool::new(false));
+        let handler_done = done.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Work| {
+            let done = handler_done.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                done.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        let dropped = tokio::time::timeout(Duration::from_millis(5), m.send(Work)).await;
+        assert!(dropped.is_err());
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(!done.load(Ordering::SeqCst));
+        m.send(Work).await.unwrap();
+        assert!(done.load(Ordering::SeqCst));
+    }
 }
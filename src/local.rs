@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::{MediatorError, Request, Result};
+
+/// The local request handler trait. Unlike the request handler, neither the local handler
+/// nor the future it returns have to be `Send`, e.g. to hold an `Rc` or a `RefCell`.
+#[async_trait(?Send)]
+pub trait LocalRequestHandler<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse>;
+}
+
+/// A mediator for local request handlers, which are not `Send`.
+///
+/// Use the `Mediator` when requests are sent from tasks spawned on a multithreaded runtime,
+/// as its handlers and their futures are `Send`. Use the `LocalMediator` when requests
+/// are sent from a single thread, e.g. with a current-thread runtime or a `LocalSet`.
+#[derive(Default)]
+pub struct LocalMediator {
+    handlers: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl LocalMediator {
+    /// Creates a new local mediator.
+    pub fn new() -> Self {
+        LocalMediator::default()
+    }
+
+    /// Registers a local request handler.
+    pub fn register_handler<TRequest, TLocalRequestHandler, TResponse>(
+        &mut self,
+        handler: TLocalRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TLocalRequestHandler: LocalRequestHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        let handler: Box<dyn LocalRequestHandler<TRequest, TResponse>> = Box::new(handler);
+        self.handlers
+            .insert(TypeId::of::<TRequest>(), Box::new(handler));
+        self
+    }
+
+    /// Send a request to the local mediator.
+    pub async fn send<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = match self.handlers.get_mut(&TypeId::of::<TRequest>()) {
+            Some(h) => h,
+            None => {
+                return Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                    std::any::type_name::<TRequest>().into(),
+                )))
+            }
+        };
+        match handler.downcast_mut::<Box<dyn LocalRequestHandler<TRequest, TResponse>>>() {
+            Some(h) => h.handle(request).await,
+            None => Err(Box::new(MediatorError::HandlerTypeMismatchError)),
+        }
+    }
+}
+
+impl std::fmt::Debug for LocalMediator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalMediator")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug)]
+    pub struct Increment;
+
+    impl Request<usize> for Increment {}
+
+    #[derive(Debug, Default)]
+    pub struct IncrementHandler(Rc<RefCell<usize>>);
+
+    #[async_trait(?Send)]
+    impl LocalRequestHandler<Increment, usize> for IncrementHandler {
+        async fn handle(&mut self, _request: Increment) -> Result<usize> {
+            *self.0.borrow_mut() += 1;
+            Ok(*self.0.borrow())
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_local_mediator_send() {
+        let counter = Rc::new(RefCell::new(0));
+        let mut m = LocalMediator::new();
+        m.register_handler(IncrementHandler(counter.clone()));
+        assert_eq!(m.send(Increment).await.unwrap(), 1);
+        assert_eq!(m.send(Increment).await.unwrap(), 2);
+        assert_eq!(*counter.borrow(), 2);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_local_mediator_no_handler_registered() {
+        let mut m = LocalMediator::new();
+        let err = m.send(Increment).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Increment>().into()
+            ))
+        );
+    }
+}
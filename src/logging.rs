@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+use crate::{Next, PipelineBehavior, Request, Result};
+
+type Redaction = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A pipeline behavior that logs every request and its response with the `log` crate.
+///
+/// Requests and responses are logged at the configured level, handler errors at the error
+/// level. The formatted output can be redacted, e.g. to hide personal data.
+pub struct LoggingBehavior {
+    level: log::Level,
+    redact: Redaction,
+}
+
+impl LoggingBehavior {
+    /// Creates a behavior logging at the given level.
+    pub fn new(level: log::Level) -> Self {
+        LoggingBehavior {
+            level,
+            redact: Box::new(str::to_string),
+        }
+    }
+
+    /// Redacts the formatted requests, responses and errors before they are logged.
+    pub fn with_redaction<F>(mut self, redact: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.redact = Box::new(redact);
+        self
+    }
+}
+
+impl Default for LoggingBehavior {
+    fn default() -> Self {
+        LoggingBehavior::new(log::Level::Debug)
+    }
+}
+
+impl std::fmt::Debug for LoggingBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoggingBehavior")
+            .field("level", &self.level)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for LoggingBehavior
+where
+    TRequest: Request<TResponse> + Debug,
+    TResponse: Debug + Send,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let request_type = std::any::type_name::<TRequest>();
+        log::log!(
+            self.level,
+            "Handling {}: {}",
+            request_type,
+            (self.redact)(&format!("{:?}", request))
+        );
+        let result = next.run(request).await;
+        match &result {
+            Ok(response) => log::log!(
+                self.level,
+                "Handled {}: {}",
+                request_type,
+                (self.redact)(&format!("{:?}", response))
+            ),
+            Err(e) => log::error!(
+                "Failed to handle {}: {}",
+                request_type,
+                (self.redact)(&e.to_string())
+            ),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::sync::Mutex;
+
+    struct TestLogger(Mutex<Vec<(log::Level, String)>>);
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            if record.target() == "brazier::logging" {
+                let entry = (record.level(), record.args().to_string());
+                self.0.lock().unwrap().push(entry);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+
+    #[derive(Debug)]
+    pub struct Login {
+        user: &'static str,
+        password: &'static str,
+    }
+
+    impl Request<String> for Login {}
+
+    #[tokio::test]
+    async fn test_logging_behavior() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Login| async move {
+            match request.password {
+                "secret" => Ok(format!("token for {}", request.user)),
+                _ => Err(format!("wrong secret for {}", request.user).into()),
+            }
+        })
+        .register_behavior_for::<Login, String, _>(
+            LoggingBehavior::new(log::Level::Info).with_redaction(|s| s.replace("secret", "***")),
+        );
+        let login = |password| Login {
+            user: "john",
+            password,
+        };
+        assert!(m.send(login("secret")).await.is_ok());
+        assert!(m.send(login("guess")).await.is_err());
+
+        let request_type = std::any::type_name::<Login>();
+        assert_eq!(
+            *LOGGER.0.lock().unwrap(),
+            vec![
+                (
+                    log::Level::Info,
+                    format!(
+                        "Handling {}: Login {{ user: \"john\", password: \"***\" }}",
+                        request_type
+                    )
+                ),
+                (
+                    log::Level::Info,
+                    format!("Handled {}: \"token for john\"", request_type)
+                ),
+                (
+                    log::Level::Info,
+                    format!(
+                        "Handling {}: Login {{ user: \"john\", password: \"guess\" }}",
+                        request_type
+                    )
+                ),
+                (
+                    log::Level::Error,
+                    format!("Failed to handle {}: wrong *** for john", request_type)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use std::marker::PhantomData;
+
+use crate::{Mediator, Request, RequestContext, RequestHandler, Result};
+
+/// Maps the responses of a request handler to another response type.
+struct MappedHandler<TRequestHandler, F, TInner> {
+    handler: TRequestHandler,
+    map: F,
+    _marker: PhantomData<fn() -> TInner>,
+}
+
+#[async_trait]
+impl<TRequest, TRequestHandler, F, TInner, TOuter> RequestHandler<TRequest, TOuter>
+    for MappedHandler<TRequestHandler, F, TInner>
+where
+    TRequest: Request<TInner> + Request<TOuter>,
+    TRequestHandler: RequestHandler<TRequest, TInner>,
+    F: Fn(TInner) -> TOuter + Send + Sync,
+    TInner: Send,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TOuter> {
+        self.handler.handle(request).await.map(&self.map)
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TOuter> {
+        self.handler
+            .handle_with_context(request, context)
+            .await
+            .map(&self.map)
+    }
+
+    async fn on_shutdown(&mut self) {
+        self.handler.on_shutdown().await
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler whose responses are mapped to another response type,
+    /// e.g. to reuse a handler for a request sent where a smaller response is expected.
+    ///
+    /// The request is sent as a `Request<TOuter>`, so it implements both `Request<TInner>`,
+    /// as required by the handler, and `Request<TOuter>`. The errors of the handler are
+    /// returned as is. As with `register_handler`, a single handler is registered for the
+    /// request type: it replaces the handler producing `TInner`, if any.
+    pub fn register_mapped_handler<TRequest, TRequestHandler, TInner, TOuter, F>(
+        &mut self,
+        handler: TRequestHandler,
+        map: F,
+    ) -> &mut Self
+    where
+        TRequest: Request<TInner> + Request<TOuter>,
+        TRequestHandler: RequestHandler<TRequest, TInner> + 'static,
+        TInner: Send + 'static,
+        TOuter: Send + 'static,
+        F: Fn(TInner) -> TOuter + Send + Sync + 'static,
+    {
+        self.register_handler::<TRequest, _, TOuter>(MappedHandler {
+            handler,
+            map,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MediatorError;
+
+    #[derive(Debug)]
+    pub struct GetUser(u32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct User {
+        id: u32,
+        name: String,
+    }
+
+    impl Request<User> for GetUser {}
+
+    impl Request<String> for GetUser {}
+
+    #[derive(Debug)]
+    pub struct GetUserHandler;
+
+    #[async_trait]
+    impl RequestHandler<GetUser, User> for GetUserHandler {
+        async fn handle(&mut self, request: GetUser) -> Result<User> {
+            match request.0 {
+                0 => Err("user not found".into()),
+                id => Ok(User {
+                    id,
+                    name: format!("user {}", id),
+                }),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_mapped_handler() {
+        let mut m = Mediator::new();
+        m.register_mapped_handler(GetUserHandler, |user: User| user.name);
+        let name: String = m.send(GetUser(1)).await.unwrap();
+        assert_eq!(name, "user 1");
+        let err = m.send::<_, String>(GetUser(0)).await.unwrap_err();
+        assert_eq!(err.to_string(), "user not found");
+        let err = m.send::<_, User>(GetUser(1)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerTypeMismatchError)
+        );
+    }
+}
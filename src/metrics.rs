@@ -0,0 +1,105 @@
+use std::{future::Future, sync::Arc, time::Duration, time::Instant};
+
+use crate::{Mediator, Result};
+
+/// The metrics recorder trait. A recorder is notified around the handling of every request
+/// sent to the mediator, e.g. to feed counters and histograms of a metrics library.
+pub trait MetricsRecorder: Send + Sync {
+    /// Called before the request is handled.
+    fn on_send_start(&self, request_type: &str);
+
+    /// Called after the request is handled.
+    fn on_send_end(&self, request_type: &str, elapsed: Duration, success: bool);
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct Recorder(pub(crate) Option<Arc<dyn MetricsRecorder>>);
+
+impl std::fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Recorder").field(&self.0.is_some()).finish()
+    }
+}
+
+/// Runs the future between the start and end hooks of the recorder.
+pub(crate) async fn record<TRequest, TResponse>(
+    recorder: &dyn MetricsRecorder,
+    future: impl Future<Output = Result<TResponse>>,
+) -> Result<TResponse> {
+    let request_type = std::any::type_name::<TRequest>();
+    recorder.on_send_start(request_type);
+    let start = Instant::now();
+    let result = future.await;
+    recorder.on_send_end(request_type, start.elapsed(), result.is_ok());
+    result
+}
+
+impl Mediator {
+    /// Sets the metrics recorder notified around every request.
+    pub fn with_recorder<TMetricsRecorder>(mut self, recorder: TMetricsRecorder) -> Self
+    where
+        TMetricsRecorder: MetricsRecorder + 'static,
+    {
+        self.recorder = Recorder(Some(Arc::new(recorder)));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MediatorError, Request};
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug, Clone, Default)]
+    pub struct TestRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl MetricsRecorder for TestRecorder {
+        fn on_send_start(&self, request_type: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("start {}", request_type));
+        }
+
+        fn on_send_end(&self, request_type: &str, _elapsed: Duration, success: bool) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("end {} {}", request_type, success));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_with_recorder() {
+        let recorder = TestRecorder::default();
+        let mut m = Mediator::new().with_recorder(recorder.clone());
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+        m.unregister_handler::<Ping, String>();
+        assert_eq!(
+            m.send(Ping)
+                .await
+                .unwrap_err()
+                .downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Ping>().into()
+            ))
+        );
+        let name = std::any::type_name::<Ping>();
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                format!("start {}", name),
+                format!("end {} true", name),
+                format!("start {}", name),
+                format!("end {} false", name),
+            ]
+        );
+    }
+}
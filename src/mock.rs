@@ -0,0 +1,185 @@
+use crate::{AnyMap, MediatorError, Request, Result};
+
+type Returning<TRequest, TResponse> = Box<dyn FnMut(&TRequest) -> Result<TResponse> + Send + Sync>;
+
+/// The expected requests of a type, with the stubbed response.
+pub struct Expectation<TRequest, TResponse> {
+    returning: Option<Returning<TRequest, TResponse>>,
+    received: Vec<TRequest>,
+}
+
+impl<TRequest, TResponse> Expectation<TRequest, TResponse> {
+    /// Sets the function returning the response to each request.
+    pub fn returning<F>(&mut self, returning: F) -> &mut Self
+    where
+        F: FnMut(&TRequest) -> Result<TResponse> + Send + Sync + 'static,
+    {
+        self.returning = Some(Box::new(returning));
+        self
+    }
+}
+
+impl<TRequest, TResponse> std::fmt::Debug for Expectation<TRequest, TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Expectation")
+            .field("returning", &self.returning.is_some())
+            .field("received", &self.received.len())
+            .finish()
+    }
+}
+
+/// A mediator returning stubbed responses, to test code sending requests
+/// without registering the real handlers.
+///
+/// The mock records the requests it receives, so they can be asserted on afterwards.
+/// A request sent with several response types has an expectation per response type.
+#[derive(Debug)]
+pub struct MockMediator {
+    /// The expectations, keyed by the request and response types.
+    expectations: AnyMap,
+}
+
+impl MockMediator {
+    /// Creates a new mock mediator without any expectation.
+    pub fn new() -> Self {
+        MockMediator {
+            expectations: AnyMap::new(),
+        }
+    }
+
+    /// Returns the expectation for a request, creating it if needed.
+    pub fn expect<TRequest, TResponse>(&mut self) -> &mut Expectation<TRequest, TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        if !self.expectations.contains::<(TRequest, TResponse)>() {
+            self.expectations
+                .set::<(TRequest, TResponse), Expectation<TRequest, TResponse>>(Expectation {
+                    returning: None,
+                    received: Vec::new(),
+                });
+        }
+        self.expectations
+            .get_mut::<(TRequest, TResponse), Expectation<TRequest, TResponse>>()
+            .expect("the expectation is set for the request and response types")
+    }
+
+    /// Send a request to the mock, returning the stubbed response.
+    ///
+    /// Returns `MediatorError::HandlerNotRegisteredError` if no response is stubbed
+    /// for the request.
+    pub async fn send<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let expectation = self.expect::<TRequest, TResponse>();
+        expectation.received.push(request);
+        match (expectation.returning.as_mut(), expectation.received.last()) {
+            (Some(returning), Some(request)) => returning(request),
+            _ => Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TRequest>().into(),
+            ))),
+        }
+    }
+
+    /// Returns the requests of a type received by the mock for the response type,
+    /// in the order they were sent.
+    pub fn received<TRequest, TResponse>(&self) -> &[TRequest]
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        self.expectations
+            .get::<(TRequest, TResponse), Expectation<TRequest, TResponse>>()
+            .map_or(&[], |e| e.received.as_slice())
+    }
+
+    /// Asserts that a request of a type was sent exactly `times` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the request was sent another number of times.
+    #[track_caller]
+    pub fn assert_sent<TRequest, TResponse>(&self, times: usize)
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let received = self.received::<TRequest, TResponse>().len();
+        assert_eq!(
+            received,
+            times,
+            "expected {} to be sent {} times, but it was sent {} times",
+            std::any::type_name::<TRequest>(),
+            times,
+            received
+        );
+    }
+}
+
+impl Default for MockMediator {
+    fn default() -> Self {
+        MockMediator::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    pub struct Ping(u32);
+
+    impl Request<String> for Ping {}
+
+    #[tokio::test]
+    async fn test_mock_mediator_returning() {
+        let mut m = MockMediator::new();
+        m.expect::<Ping, String>()
+            .returning(|request| Ok(format!("pong {}", request.0)));
+        assert_eq!(m.send(Ping(1)).await.unwrap(), "pong 1");
+        assert_eq!(m.send(Ping(2)).await.unwrap(), "pong 2");
+        assert_eq!(m.received::<Ping, String>(), &[Ping(1), Ping(2)]);
+        m.assert_sent::<Ping, String>(2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_mediator_no_response_stubbed() {
+        let mut m = MockMediator::new();
+        let err = m.send::<Ping, String>(Ping(1)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Ping>().into()
+            ))
+        );
+        m.assert_sent::<Ping, String>(1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_mediator_several_response_types() {
+        #[derive(Debug, PartialEq)]
+        pub struct Quote(u32);
+
+        impl Request<String> for Quote {}
+
+        impl Request<u32> for Quote {}
+
+        let mut m = MockMediator::new();
+        m.expect::<Quote, String>()
+            .returning(|request| Ok(format!("quote {}", request.0)));
+        m.expect::<Quote, u32>().returning(|request| Ok(request.0));
+        assert_eq!(m.send::<_, String>(Quote(1)).await.unwrap(), "quote 1");
+        assert_eq!(m.send::<_, u32>(Quote(2)).await.unwrap(), 2);
+        assert_eq!(m.received::<Quote, String>(), &[Quote(1)]);
+        assert_eq!(m.received::<Quote, u32>(), &[Quote(2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "to be sent 1 times, but it was sent 0 times")]
+    fn test_mock_mediator_assert_sent_fails() {
+        MockMediator::new().assert_sent::<Ping, String>(1);
+    }
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use std::{any::Any, collections::HashMap, marker::PhantomData};
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result};
+
+/// A request handler taking and returning type-erased values.
+#[async_trait]
+pub(crate) trait AnyRequestHandler: Send + Sync {
+    async fn handle(&mut self, request: Box<dyn Any + Send>) -> Result<Box<dyn Any + Send>>;
+
+    /// Calls `on_shutdown` on the erased handler. Defaults to doing nothing.
+    async fn on_shutdown(&mut self) {}
+}
+
+struct AnyRequestHandlerAdapter<TRequestHandler, TRequest, TResponse> {
+    handler: TRequestHandler,
+    _marker: PhantomData<fn(TRequest) -> TResponse>,
+}
+
+#[async_trait]
+impl<TRequestHandler, TRequest, TResponse> AnyRequestHandler
+    for AnyRequestHandlerAdapter<TRequestHandler, TRequest, TResponse>
+where
+    TRequestHandler: RequestHandler<TRequest, TResponse>,
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    async fn handle(&mut self, request: Box<dyn Any + Send>) -> Result<Box<dyn Any + Send>> {
+        let request = match request.downcast::<TRequest>() {
+            Ok(request) => *request,
+            Err(_) => return Err(Box::new(MediatorError::RequestTypeMismatchError)),
+        };
+        let response = self.handler.handle(request).await?;
+        Ok(Box::new(response))
+    }
+
+    async fn on_shutdown(&mut self) {
+        self.handler.on_shutdown().await
+    }
+}
+
+pub(crate) fn erase<TRequest, TRequestHandler, TResponse>(
+    handler: TRequestHandler,
+) -> Box<dyn AnyRequestHandler>
+where
+    TRequest: Request<TResponse>,
+    TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+    TResponse: Send + 'static,
+{
+    Box::new(AnyRequestHandlerAdapter {
+        handler,
+        _marker: PhantomData,
+    })
+}
+
+/// The fallback handler trait. A fallback handler handles the named requests
+/// for which no handler is registered.
+#[async_trait]
+pub trait FallbackHandler: Send + Sync {
+    /// The method that handles the request sent under the given name.
+    async fn handle(
+        &mut self,
+        name: &str,
+        request: Box<dyn Any + Send>,
+    ) -> Result<Box<dyn Any + Send>>;
+}
+
+#[derive(Default)]
+pub(crate) struct NamedHandlers {
+    handlers: HashMap<String, Box<dyn AnyRequestHandler>>,
+    fallback: Option<Box<dyn FallbackHandler>>,
+}
+
+impl std::fmt::Debug for NamedHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<_> = self.handlers.keys().collect();
+        names.sort_unstable();
+        f.debug_struct("NamedHandlers")
+            .field("handlers", &names)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl NamedHandlers {
+    /// Calls `on_shutdown` on every named handler.
+    pub(crate) async fn shutdown(&mut self) {
+        for handler in self.handlers.values_mut() {
+            handler.on_shutdown().await;
+        }
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler under a name, for dispatching requests whose type
+    /// is only known at runtime.
+    ///
+    /// Registering another handler under the same name replaces the previous one.
+    pub fn register_named_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        name: &str,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.named_handlers
+            .handlers
+            .insert(name.to_string(), erase(handler));
+        self
+    }
+
+    /// Sets the handler invoked when no handler is registered under the name of a named request.
+    ///
+    /// Setting another fallback handler replaces the previous one.
+    pub fn set_fallback_handler<TFallbackHandler>(&mut self, handler: TFallbackHandler) -> &mut Self
+    where
+        TFallbackHandler: FallbackHandler + 'static,
+    {
+        self.named_handlers.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Send a type-erased request to the handler registered under the given name.
+    ///
+    /// Returns `MediatorError::RequestTypeMismatchError` if the request is not of the type
+    /// expected by the handler. The response has to be downcast by the caller.
+    ///
+    /// When no handler is registered under the name, the request is sent to the fallback
+    /// handler, if any.
+    pub async fn send_named(
+        &mut self,
+        name: &str,
+        request: Box<dyn Any + Send>,
+    ) -> Result<Box<dyn Any + Send>> {
+        if let Some(h) = self.named_handlers.handlers.get_mut(name) {
+            return h.handle(request).await;
+        }
+        match self.named_handlers.fallback.as_mut() {
+            Some(h) => h.handle(name, request).await,
+            None => Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                name.to_string().into(),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Greet(String);
+
+    impl Request<String> for Greet {}
+
+    #[derive(Debug)]
+    pub struct GreetHandler;
+
+    #[async_trait]
+    impl RequestHandler<Greet, String> for GreetHandler {
+        async fn handle(&mut self, request: Greet) -> Result<String> {
+            Ok(format!("Hello, {}!", request.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_named() {
+        let mut m = Mediator::new();
+        m.register_named_handler("greet", GreetHandler);
+        let response = m
+            .send_named("greet", Box::new(Greet(String::from("world"))))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.downcast_ref::<String>().map(String::as_str),
+            Some("Hello, world!")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_named_wrong_request_type() {
+        let mut m = Mediator::new();
+        m.register_named_handler("greet", GreetHandler);
+        let err = m.send_named("greet", Box::new(42)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::RequestTypeMismatchError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_named_no_handler_registered() {
+        let mut m = Mediator::new();
+        let err = m.send_named("greet", Box::new(42)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError("greet".into()))
+        );
+    }
+
+    #[derive(Debug)]
+    pub struct EchoFallbackHandler;
+
+    #[async_trait]
+    impl FallbackHandler for EchoFallbackHandler {
+        async fn handle(
+            &mut self,
+            name: &str,
+            _request: Box<dyn Any + Send>,
+        ) -> Result<Box<dyn Any + Send>> {
+            Ok(Box::new(name.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_named_fallback_handler() {
+        let mut m = Mediator::new();
+        m.register_named_handler("greet", GreetHandler)
+            .set_fallback_handler(EchoFallbackHandler);
+        let response = m
+            .send_named("greet", Box::new(Greet(String::from("world"))))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.downcast_ref::<String>().map(String::as_str),
+            Some("Hello, world!")
+        );
+        let response = m.send_named("unknown", Box::new(42)).await.unwrap();
+        assert_eq!(
+            response.downcast_ref::<String>().map(String::as_str),
+            Some("unknown")
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::{Mediator, MediatorError, Result};
+
+/// The notification trait.
+pub trait Notification: Send + Sync + 'static {}
+
+/// The notification handler trait. Any number of handlers can be registered for a notification.
+#[async_trait]
+pub trait NotificationHandler<TNotification>: Send + Sync
+where
+    TNotification: Notification,
+{
+    /// The method that handles the notification.
+    async fn handle(&mut self, notification: &TNotification) -> Result<()>;
+}
+
+/// The handlers of a notification with their priority, sorted by priority.
+pub(crate) type NotificationHandlers<TNotification> =
+    Vec<(i32, Box<dyn NotificationHandler<TNotification>>)>;
+
+impl Mediator {
+    /// Registers a notification handler, with the default priority of 0.
+    ///
+    /// Handlers registered for the same notification with the same priority
+    /// are invoked in registration order.
+    pub fn register_notification_handler<TNotification, TNotificationHandler>(
+        &mut self,
+        handler: TNotificationHandler,
+    ) -> &mut Self
+    where
+        TNotification: Notification,
+        TNotificationHandler: NotificationHandler<TNotification> + 'static,
+    {
+        self.register_notification_handler_with_priority(handler, 0)
+    }
+
+    /// Registers a notification handler with a priority.
+    ///
+    /// Handlers with a lower priority are invoked first. The ordering is stable:
+    /// handlers with the same priority are invoked in registration order.
+    pub fn register_notification_handler_with_priority<TNotification, TNotificationHandler>(
+        &mut self,
+        handler: TNotificationHandler,
+        priority: i32,
+    ) -> &mut Self
+    where
+        TNotification: Notification,
+        TNotificationHandler: NotificationHandler<TNotification> + 'static,
+    {
+        match self
+            .notification_handlers
+            .get_mut::<TNotification, NotificationHandlers<TNotification>>()
+            .ok()
+        {
+            Some(handlers) => {
+                let index = handlers.partition_point(|(p, _)| *p <= priority);
+                handlers.insert(index, (priority, Box::new(handler)));
+            }
+            None => self
+                .notification_handlers
+                .set::<TNotification, NotificationHandlers<TNotification>>(vec![(
+                    priority,
+                    Box::new(handler),
+                )]),
+        }
+        self
+    }
+
+    /// Returns the number of handlers registered for the notification.
+    pub fn notification_handler_count<TNotification>(&self) -> usize
+    where
+        TNotification: Notification,
+    {
+        self.notification_handlers
+            .get::<TNotification, NotificationHandlers<TNotification>>()
+            .map_or(0, Vec::len)
+    }
+
+    /// Publish a notification to every registered handler, in priority order.
+    ///
+    /// The notification goes to the handlers registered at the time it is published,
+    /// including those registered after the mediator was built.
+    /// Publishing a notification without any registered handler succeeds.
+    /// The first error returned by a handler stops the publishing and is returned.
+    pub async fn publish<TNotification>(&mut self, notification: TNotification) -> Result<()>
+    where
+        TNotification: Notification,
+    {
+        if let Ok(handlers) = self
+            .notification_handlers
+            .get_mut::<TNotification, NotificationHandlers<TNotification>>()
+        {
+            for (_, handler) in handlers.iter_mut() {
+                handler.handle(&notification).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Publish a notification to every registered handler, in priority order,
+    /// requiring at least one handler.
+    ///
+    /// Returns `MediatorError::NoSubscribersError` if no handler is registered for the notification.
+    pub async fn publish_required<TNotification>(
+        &mut self,
+        notification: TNotification,
+    ) -> Result<()>
+    where
+        TNotification: Notification,
+    {
+        if self.notification_handler_count::<TNotification>() == 0 {
+            return Err(Box::new(MediatorError::NoSubscribersError(
+                std::any::type_name::<TNotification>(),
+            )));
+        }
+        self.publish(notification).await
+    }
+
+    /// Publish a notification to every registered handler concurrently.
+    ///
+    /// The handlers are polled concurrently on the current task, not spawned,
+    /// and every handler runs to completion even if another one fails.
+    /// A single error is returned as is, several errors are returned as `MediatorError::AggregateError`.
+    /// Use `publish` when handlers rely on being invoked in registration order.
+    pub async fn publish_parallel<TNotification>(
+        &mut self,
+        notification: TNotification,
+    ) -> Result<()>
+    where
+        TNotification: Notification,
+    {
+        let handlers = match self
+            .notification_handlers
+            .get_mut::<TNotification, NotificationHandlers<TNotification>>()
+            .ok()
+        {
+            Some(handlers) => handlers,
+            None => return Ok(()),
+        };
+        let results = futures::future::join_all(
+            handlers
+                .iter_mut()
+                .map(|(_, handler)| handler.handle(&notification)),
+        )
+        .await;
+        let mut errors: Vec<_> = results.into_iter().filter_map(|r| r.err()).collect();
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Box::new(MediatorError::AggregateError(
+                errors.into_iter().map(Arc::from).collect(),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    pub struct TestNotification(&'static str);
+
+    impl Notification for TestNotification {}
+
+    #[derive(Debug)]
+    pub struct TestNotificationHandler {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl NotificationHandler<TestNotification> for TestNotificationHandler {
+        async fn handle(&mut self, notification: &TestNotification) -> Result<()> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{}:{}", self.name, notification.0));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_in_registration_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_notification_handler(TestNotificationHandler {
+            name: "first",
+            log: log.clone(),
+        });
+        m.register_notification_handler(TestNotificationHandler {
+            name: "second",
+            log: log.clone(),
+        });
+        assert_eq!(m.notification_handler_count::<TestNotification>(), 2);
+        m.publish(TestNotification("hello")).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["first:hello", "second:hello"]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_to_late_registered_handlers() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::builder()
+            .add_notification_handler(TestNotificationHandler {
+                name: "first",
+                log: log.clone(),
+            })
+            .build()
+            .unwrap();
+        m.publish(TestNotification("hello")).await.unwrap();
+        m.register_notification_handler(TestNotificationHandler {
+            name: "second",
+            log: log.clone(),
+        });
+        m.publish(TestNotification("again")).await.unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["first:hello", "first:again", "second:again"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_in_priority_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        for (name, priority) in [("a", 10), ("b", -5), ("c", 0), ("d", 10), ("e", -5)] {
+            m.register_notification_handler_with_priority(
+                TestNotificationHandler {
+                    name,
+                    log: log.clone(),
+                },
+                priority,
+            );
+        }
+        m.publish(TestNotification("hi")).await.unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["b:hi", "e:hi", "c:hi", "a:hi", "d:hi"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_without_handlers() {
+        let mut m = Mediator::new();
+        assert_eq!(m.notification_handler_count::<TestNotification>(), 0);
+        assert!(m.publish(TestNotification("hello")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_required() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        let err = m
+            .publish_required(TestNotification("hello"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "No handler registered for notification {}",
+                std::any::type_name::<TestNotification>()
+            )
+        );
+        m.register_notification_handler(TestNotificationHandler {
+            name: "first",
+            log: log.clone(),
+        });
+        m.publish_required(TestNotification("hello")).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["first:hello"]);
+    }
+
+    #[derive(Debug)]
+    pub struct FailingNotificationHandler(&'static str);
+
+    #[async_trait]
+    impl NotificationHandler<TestNotification> for FailingNotificationHandler {
+        async fn handle(&mut self, _notification: &TestNotification) -> Result<()> {
+            Err(self.0.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_parallel() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_notification_handler(TestNotificationHandler {
+            name: "first",
+            log: log.clone(),
+        });
+        m.register_notification_handler(TestNotificationHandler {
+            name: "second",
+            log: log.clone(),
+        });
+        m.publish_parallel(TestNotification("hello")).await.unwrap();
+        let mut log = log.lock().unwrap().clone();
+        log.sort();
+        assert_eq!(log, vec!["first:hello", "second:hello"]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_publish_parallel_aggregates_errors() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_notification_handler(FailingNotificationHandler("first"));
+        m.register_notification_handler(TestNotificationHandler {
+            name: "second",
+            log: log.clone(),
+        });
+        m.register_notification_handler(FailingNotificationHandler("third"));
+        let err = m
+            .publish_parallel(TestNotification("hello"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::AggregateError(vec![
+                Arc::from(Box::<dyn std::error::Error + Send + Sync>::from("first")),
+                Arc::from(Box::<dyn std::error::Error + Send + Sync>::from("third")),
+            ]))
+        );
+        assert_eq!(*log.lock().unwrap(), vec!["second:hello"]);
+    }
+}
@@ -0,0 +1,136 @@
+/// A response which is one of two types, for handlers returning different response shapes.
+///
+/// `From<A>` and `From<B>` cannot both be implemented, as `A` and `B` may be the same type,
+/// so the variants are constructed directly. `OneOf` converts from and into a `Result`.
+///
+/// ```
+/// use brazier::{OneOf, Request};
+///
+/// pub struct Found(pub String);
+/// pub struct Redirect(pub String);
+///
+/// pub struct GetPage(pub String);
+///
+/// impl Request<OneOf<Found, Redirect>> for GetPage {}
+///
+/// fn describe(response: OneOf<Found, Redirect>) -> String {
+///     match response {
+///         OneOf::First(Found(page)) => page,
+///         OneOf::Second(Redirect(url)) => format!("see {url}"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OneOf<A, B> {
+    /// A response of the first type.
+    First(A),
+    /// A response of the second type.
+    Second(B),
+}
+
+impl<A, B> OneOf<A, B> {
+    /// Returns whether the response is of the first type.
+    pub fn is_first(&self) -> bool {
+        matches!(self, OneOf::First(_))
+    }
+
+    /// Returns whether the response is of the second type.
+    pub fn is_second(&self) -> bool {
+        matches!(self, OneOf::Second(_))
+    }
+
+    /// Returns the response of the first type, if it is one.
+    pub fn first(&self) -> Option<&A> {
+        match self {
+            OneOf::First(a) => Some(a),
+            OneOf::Second(_) => None,
+        }
+    }
+
+    /// Returns the response of the second type, if it is one.
+    pub fn second(&self) -> Option<&B> {
+        match self {
+            OneOf::First(_) => None,
+            OneOf::Second(b) => Some(b),
+        }
+    }
+
+    /// Converts into the response of the first type, if it is one.
+    pub fn into_first(self) -> Option<A> {
+        match self {
+            OneOf::First(a) => Some(a),
+            OneOf::Second(_) => None,
+        }
+    }
+
+    /// Converts into the response of the second type, if it is one.
+    pub fn into_second(self) -> Option<B> {
+        match self {
+            OneOf::First(_) => None,
+            OneOf::Second(b) => Some(b),
+        }
+    }
+
+    /// Converts both types of response into a single type.
+    pub fn fold<T>(self, first: impl FnOnce(A) -> T, second: impl FnOnce(B) -> T) -> T {
+        match self {
+            OneOf::First(a) => first(a),
+            OneOf::Second(b) => second(b),
+        }
+    }
+}
+
+impl<A, B> From<Result<A, B>> for OneOf<A, B> {
+    fn from(result: Result<A, B>) -> Self {
+        match result {
+            Ok(a) => OneOf::First(a),
+            Err(b) => OneOf::Second(b),
+        }
+    }
+}
+
+impl<A, B> From<OneOf<A, B>> for Result<A, B> {
+    fn from(one_of: OneOf<A, B>) -> Self {
+        match one_of {
+            OneOf::First(a) => Ok(a),
+            OneOf::Second(b) => Err(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Mediator, Request};
+
+    #[derive(Debug)]
+    pub struct Parse(&'static str);
+
+    impl Request<OneOf<i64, String>> for Parse {}
+
+    #[tokio::test]
+    async fn test_mediator_send_one_of() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Parse| async move {
+            Ok(request
+                .0
+                .parse()
+                .map_err(|_| request.0.to_uppercase())
+                .into())
+        });
+        let response = m.send(Parse("42")).await.unwrap();
+        assert_eq!(response.first(), Some(&42));
+        let response = m.send(Parse("nan")).await.unwrap();
+        assert_eq!(response.into_second().as_deref(), Some("NAN"));
+    }
+
+    #[test]
+    fn test_one_of_accessors() {
+        let one_of: OneOf<i64, &str> = OneOf::Second("two");
+        assert!(one_of.is_second());
+        assert!(!one_of.is_first());
+        assert_eq!(one_of.first(), None);
+        assert_eq!(one_of.fold(|a| a.to_string(), str::to_string), "two");
+        assert_eq!(Result::from(one_of), Err("two"));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{Mediator, Request, RequestHandler};
+
+impl Mediator {
+    /// Registers a clone of an open generic handler for the request.
+    ///
+    /// An open generic handler implements `RequestHandler` for every request type satisfying
+    /// a bound, e.g. `impl<T: AuditableRequest> RequestHandler<T, AuditEntry> for AuditHandler`.
+    /// Rust cannot instantiate a generic handler at runtime for a request type it has not
+    /// seen, so the handler has to be registered once per concrete request type. Each request
+    /// type gets its own clone of the handler; state shared across the request types has to be
+    /// held behind an `Arc`.
+    pub fn register_open_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: &TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + Clone + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_handler(handler.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Result;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    pub trait AuditableRequest: Request<String> {
+        fn action(&self) -> &'static str;
+    }
+
+    #[derive(Debug)]
+    pub struct CreateUser;
+
+    impl Request<String> for CreateUser {}
+
+    impl AuditableRequest for CreateUser {
+        fn action(&self) -> &'static str {
+            "create"
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct DeleteUser;
+
+    impl Request<String> for DeleteUser {}
+
+    impl AuditableRequest for DeleteUser {
+        fn action(&self) -> &'static str {
+            "delete"
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct AuditHandler(Arc<Mutex<Vec<&'static str>>>);
+
+    #[async_trait]
+    impl<TRequest: AuditableRequest> RequestHandler<TRequest, String> for AuditHandler {
+        async fn handle(&mut self, request: TRequest) -> Result<String> {
+            self.0.lock().unwrap().push(request.action());
+            Ok(format!("audited {}", request.action()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_open_handler() {
+        let handler = AuditHandler::default();
+        let mut m = Mediator::new();
+        m.register_open_handler::<CreateUser, _, String>(&handler)
+            .register_open_handler::<DeleteUser, _, String>(&handler);
+        assert_eq!(m.send(CreateUser).await.unwrap(), "audited create");
+        assert_eq!(m.send(DeleteUser).await.unwrap(), "audited delete");
+        assert_eq!(*handler.0.lock().unwrap(), vec!["create", "delete"]);
+    }
+}
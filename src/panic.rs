@@ -0,0 +1,78 @@
+use futures::FutureExt;
+use std::{any::Any, panic::AssertUnwindSafe};
+
+use crate::{Mediator, MediatorError, Request, RequestContext, Result};
+
+/// Returns the message of a panic, when it panicked with a string.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    }
+}
+
+impl Mediator {
+    /// Send a request to the mediator, failing with `MediatorError::HandlerPanickedError`
+    /// if the handler or a behavior panics, instead of unwinding through the caller.
+    ///
+    /// The handler is kept registered after it panicked, although it may have been left
+    /// in an inconsistent state, which is why catching panics is opt-in.
+    /// Panics are only caught when the panic strategy is `unwind`.
+    pub async fn send_catch_panic<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let context = RequestContext::new();
+        match AssertUnwindSafe(self.send_inner(request, &context, None))
+            .catch_unwind()
+            .await
+        {
+            Ok(response) => response,
+            Err(payload) => Err(Box::new(MediatorError::HandlerPanickedError(
+                panic_message(&*payload),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Divide(i64, i64);
+
+    impl Request<i64> for Divide {}
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Divide| async move {
+            if request.1 == 0 {
+                panic!("division of {} by zero", request.0);
+            }
+            Ok(request.0 / request.1)
+        });
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_catch_panic() {
+        let mut m = mediator();
+        assert_eq!(m.send_catch_panic(Divide(42, 2)).await.unwrap(), 21);
+        let err = m.send_catch_panic(Divide(42, 0)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerPanickedError(String::from(
+                "division of 42 by zero"
+            )))
+        );
+        assert_eq!(m.send(Divide(42, 6)).await.unwrap(), 7);
+    }
+}
@@ -0,0 +1,216 @@
+use std::any::TypeId;
+
+use crate::{
+    behavior::GlobalBehaviors, chain::ChainableHandlerSlot, error_hook::ErrorHook, info,
+    metrics::Recorder, notification::NotificationHandlers, AnyMap, ChainableHandler, Mediator,
+    Notification, NotificationHandler, Request, RequestHandler,
+};
+
+/// The handlers taken out of a mediator with `Mediator::into_parts`.
+///
+/// The handlers can be taken one by one, or moved into a new mediator with
+/// `Mediator::from_parts`.
+pub struct MediatorParts(Mediator);
+
+impl MediatorParts {
+    /// Takes the request handler registered for the request.
+    pub fn take_handler<TRequest, TResponse>(
+        &mut self,
+    ) -> Option<Box<dyn RequestHandler<TRequest, TResponse>>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let handler = self
+            .0
+            .handlers
+            .remove::<TRequest, Box<dyn RequestHandler<TRequest, TResponse>>>()?;
+        self.0.handler_infos.remove(&TypeId::of::<TRequest>());
+        Some(handler)
+    }
+
+    /// Takes the chainable handler registered for the request.
+    pub fn take_chainable_handler<TRequest, TResponse>(
+        &mut self,
+    ) -> Option<Box<dyn ChainableHandler<TRequest, TResponse>>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let slot = self
+            .0
+            .chainable_handlers
+            .remove::<TRequest, ChainableHandlerSlot<TRequest, TResponse>>()?;
+        self.0.handler_infos.remove(&TypeId::of::<TRequest>());
+        let handler = slot.lock().unwrap().take();
+        handler
+    }
+
+    /// Takes the handlers registered for the notification, in priority order.
+    pub fn take_notification_handlers<TNotification>(
+        &mut self,
+    ) -> Vec<Box<dyn NotificationHandler<TNotification>>>
+    where
+        TNotification: Notification,
+    {
+        self.0
+            .notification_handlers
+            .remove::<TNotification, NotificationHandlers<TNotification>>()
+            .map(|handlers| handlers.into_iter().map(|(_, handler)| handler).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the type names of the requests with a registered handler, sorted by name.
+    pub fn registered_requests(&self) -> Vec<&'static str> {
+        info::request_type_names(&self.0.handler_infos)
+    }
+}
+
+impl std::fmt::Debug for MediatorParts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediatorParts")
+            .field("handlers", &self.registered_requests())
+            .field("read_handlers", &self.0.read_handlers.len())
+            .field("notification_handlers", &self.0.notification_handlers.len())
+            .field("stream_handlers", &self.0.stream_handlers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Mediator {
+    /// Consumes the mediator, returning all of its handlers, with their default timeouts,
+    /// and its services.
+    ///
+    /// The behaviors, the processors, the latency behavior, the metrics recorder and the
+    /// error hook are dropped.
+    pub fn into_parts(self) -> MediatorParts {
+        MediatorParts(Mediator {
+            behaviors: AnyMap::new(),
+            global_behaviors: GlobalBehaviors::default(),
+            pre_processors: AnyMap::new(),
+            post_processors: AnyMap::new(),
+            latency: None,
+            recorder: Recorder::default(),
+            error_hook: ErrorHook::default(),
+            ..self
+        })
+    }
+
+    /// Creates a new mediator with the handlers left in the parts.
+    pub fn from_parts(parts: MediatorParts) -> Self {
+        parts.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{RequestPreProcessor, Result, SyncRequestHandler};
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug)]
+    pub struct Pong;
+
+    impl Request<String> for Pong {}
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) })
+            .register_handler_fn(|_: Pong| async { Ok(String::from("ping!")) });
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_into_parts_take_handler() {
+        let mut parts = mediator().into_parts();
+        let mut handler = parts.take_handler::<Ping, String>().unwrap();
+        assert!(parts.take_handler::<Ping, String>().is_none());
+        assert_eq!(handler.handle(Ping).await.unwrap(), "pong!");
+        assert_eq!(
+            parts.registered_requests(),
+            vec![std::any::type_name::<Pong>()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_from_parts() {
+        #[derive(Debug)]
+        pub struct RejectAll;
+
+        #[async_trait]
+        impl RequestPreProcessor<Ping> for RejectAll {
+            async fn process(&mut self, _request: &Ping) -> Result<()> {
+                Err("rejected".into())
+            }
+        }
+
+        let mut m = mediator();
+        m.register_pre_processor(RejectAll);
+        assert!(m.send(Ping).await.is_err());
+        let mut m = Mediator::from_parts(m.into_parts());
+        assert_eq!(m.handler_count(), 2);
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+    }
+
+    #[derive(Debug)]
+    pub struct PongHandler;
+
+    #[async_trait]
+    impl RequestHandler<Ping, String> for PongHandler {
+        async fn handle(&mut self, _request: Ping) -> Result<String> {
+            Ok(String::from("keyed pong!"))
+        }
+    }
+
+    impl SyncRequestHandler<Ping, String> for PongHandler {
+        fn handle(&mut self, _request: Ping) -> Result<String> {
+            Ok(String::from("sync pong!"))
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct PingTwice;
+
+    #[async_trait]
+    impl ChainableHandler<Pong, String> for PingTwice {
+        async fn handle(&mut self, _request: Pong, mediator: &mut Mediator) -> Result<String> {
+            Ok(mediator.send(Ping).await? + &mediator.send(Ping).await?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_from_parts_keeps_every_handler() {
+        let mut m = mediator();
+        m.register_keyed_handler("keyed", PongHandler)
+            .register_sync_handler(PongHandler)
+            .register_named_handler("named", PongHandler)
+            .register_service(42u32);
+        let mut m = Mediator::from_parts(m.into_parts());
+        assert_eq!(m.send_keyed("keyed", Ping).await.unwrap(), "keyed pong!");
+        assert_eq!(m.send_sync(Ping).unwrap(), "sync pong!");
+        let response = m.send_named("named", Box::new(Ping)).await.unwrap();
+        assert_eq!(*response.downcast::<String>().unwrap(), "keyed pong!");
+        assert_eq!(m.services.resolve::<u32>().as_deref(), Some(&42));
+    }
+
+    #[tokio::test]
+    async fn test_mediator_into_parts_take_chainable_handler() {
+        let mut m = mediator();
+        m.register_chainable_handler(PingTwice);
+        let mut parts = m.into_parts();
+        assert!(parts.take_handler::<Pong, String>().is_none());
+        let mut handler = parts.take_chainable_handler::<Pong, String>().unwrap();
+        assert!(parts.take_chainable_handler::<Pong, String>().is_none());
+        assert_eq!(
+            parts.registered_requests(),
+            vec![std::any::type_name::<Ping>()]
+        );
+        let mut m = Mediator::from_parts(parts);
+        assert_eq!(handler.handle(Pong, &mut m).await.unwrap(), "pong!pong!");
+    }
+}
@@ -0,0 +1,189 @@
+use async_trait::async_trait;
+
+use crate::{Mediator, Request, Result};
+
+/// The request pre-processor trait. Pre-processors run before the request is handled.
+#[async_trait]
+pub trait RequestPreProcessor<TRequest>: Send + Sync
+where
+    TRequest: Send + Sync + 'static,
+{
+    /// The method that processes the request.
+    async fn process(&mut self, request: &TRequest) -> Result<()>;
+}
+
+pub(crate) type PreProcessors<TRequest> = Vec<Box<dyn RequestPreProcessor<TRequest>>>;
+
+/// The request post-processor trait. Post-processors run after the request is successfully handled.
+#[async_trait]
+pub trait RequestPostProcessor<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that processes the request and its response.
+    async fn process(&mut self, request: &TRequest, response: &TResponse) -> Result<()>;
+}
+
+pub(crate) struct PostProcessors<TRequest, TResponse> {
+    /// Keeps a copy of the request, as the handler takes ownership of it.
+    pub(crate) clone_request: fn(&TRequest) -> TRequest,
+    pub(crate) post_processors: Vec<Box<dyn RequestPostProcessor<TRequest, TResponse>>>,
+}
+
+impl Mediator {
+    /// Registers a request pre-processor.
+    ///
+    /// Pre-processors run in registration order, before the pipeline behaviors and the handler.
+    /// The first error returned by a pre-processor is returned without handling the request.
+    pub fn register_pre_processor<TRequest, TRequestPreProcessor>(
+        &mut self,
+        pre_processor: TRequestPreProcessor,
+    ) -> &mut Self
+    where
+        TRequest: Send + Sync + 'static,
+        TRequestPreProcessor: RequestPreProcessor<TRequest> + 'static,
+    {
+        match self
+            .pre_processors
+            .get_mut::<TRequest, PreProcessors<TRequest>>()
+            .ok()
+        {
+            Some(pre_processors) => pre_processors.push(Box::new(pre_processor)),
+            None => self
+                .pre_processors
+                .set::<TRequest, PreProcessors<TRequest>>(vec![Box::new(pre_processor)]),
+        }
+        self
+    }
+
+    /// Registers a request post-processor.
+    ///
+    /// Post-processors run in registration order, after the handler returned a response.
+    /// The request is cloned before being handled, so post-processors can read it.
+    /// The first error returned by a post-processor is returned instead of the response.
+    pub fn register_post_processor<TRequest, TRequestPostProcessor, TResponse>(
+        &mut self,
+        post_processor: TRequestPostProcessor,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse> + Clone,
+        TRequestPostProcessor: RequestPostProcessor<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        match self
+            .post_processors
+            .get_mut::<TRequest, PostProcessors<TRequest, TResponse>>()
+            .ok()
+        {
+            Some(post_processors) => post_processors
+                .post_processors
+                .push(Box::new(post_processor)),
+            None => self
+                .post_processors
+                .set::<TRequest, PostProcessors<TRequest, TResponse>>(PostProcessors {
+                    clone_request: TRequest::clone,
+                    post_processors: vec![Box::new(post_processor)],
+                }),
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Request;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    pub struct TestRequest(i64);
+
+    impl Request<i64> for TestRequest {}
+
+    #[derive(Debug)]
+    pub struct RejectNegative;
+
+    #[async_trait]
+    impl RequestPreProcessor<TestRequest> for RejectNegative {
+        async fn process(&mut self, request: &TestRequest) -> Result<()> {
+            if request.0 < 0 {
+                return Err("negative".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Audit(Arc<Mutex<Vec<i64>>>);
+
+    #[async_trait]
+    impl RequestPreProcessor<TestRequest> for Audit {
+        async fn process(&mut self, request: &TestRequest) -> Result<()> {
+            self.0.lock().unwrap().push(request.0);
+            Ok(())
+        }
+    }
+
+    fn mediator(audit: &Arc<Mutex<Vec<i64>>>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_pre_processor(RejectNegative)
+            .register_pre_processor(Audit(audit.clone()))
+            .register_handler_fn(|request: TestRequest| async move { Ok(request.0) });
+        m
+    }
+
+    #[tokio::test]
+    async fn test_mediator_pre_processors() {
+        let audit = Arc::new(Mutex::new(Vec::new()));
+        let mut m = mediator(&audit);
+        assert_eq!(m.send(TestRequest(42)).await.unwrap(), 42);
+        assert_eq!(*audit.lock().unwrap(), vec![42]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_pre_processor_short_circuits() {
+        let audit = Arc::new(Mutex::new(Vec::new()));
+        let mut m = mediator(&audit);
+        assert!(m.send(TestRequest(-1)).await.is_err());
+        assert!(audit.lock().unwrap().is_empty());
+    }
+
+    #[derive(Debug)]
+    pub struct Collect(Arc<Mutex<Vec<(i64, i64)>>>);
+
+    #[async_trait]
+    impl RequestPostProcessor<TestRequest, i64> for Collect {
+        async fn process(&mut self, request: &TestRequest, response: &i64) -> Result<()> {
+            self.0.lock().unwrap().push((request.0, *response));
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Fail;
+
+    #[async_trait]
+    impl RequestPostProcessor<TestRequest, i64> for Fail {
+        async fn process(&mut self, _request: &TestRequest, _response: &i64) -> Result<()> {
+            Err("post-processor failed".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_post_processors() {
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_post_processor(Collect(collected.clone()))
+            .register_handler_fn(|request: TestRequest| async move { Ok(request.0 * 2) });
+        assert_eq!(m.send(TestRequest(21)).await.unwrap(), 42);
+        assert_eq!(*collected.lock().unwrap(), vec![(21, 42)]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_post_processor_error() {
+        let mut m = Mediator::new();
+        m.register_post_processor(Fail)
+            .register_handler_fn(|request: TestRequest| async move { Ok(request.0) });
+        assert!(m.send(TestRequest(42)).await.is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use futures::{channel::mpsc, Stream};
+use std::{future::Future, pin::Pin};
+
+use crate::{Mediator, Request, RequestContext, Result, SendFuture};
+
+/// The progress request trait. A progress request reports its progress while it is handled,
+/// e.g. for a long command shown with a progress bar.
+pub trait ProgressRequest<TResponse>: Request<TResponse> {
+    /// The type of the progress reported by the handler.
+    type Progress: Send + 'static;
+}
+
+/// Reports the progress of a request, found in the context of a progress request.
+///
+/// Handlers get it with `context.get::<ProgressReporter<_>>()` in `handle_with_context`.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter<TProgress>(mpsc::UnboundedSender<TProgress>);
+
+impl<TProgress> ProgressReporter<TProgress> {
+    /// Reports the progress of the request.
+    ///
+    /// The progress is ignored once the stream of progress is dropped.
+    pub fn report(&self, progress: TProgress) {
+        let _ = self.0.unbounded_send(progress);
+    }
+}
+
+/// The stream of progress reported by the handler of a progress request.
+pub type ProgressStream<TProgress> = Pin<Box<dyn Stream<Item = TProgress> + Send>>;
+
+impl Mediator {
+    /// Send a progress request to the mediator, returning the stream of its progress
+    /// along with its response.
+    ///
+    /// The stream ends once the request is handled. The progress is buffered without limit,
+    /// so the handler never waits for a slow consumer of the stream; consume it while awaiting
+    /// the response when the handler reports a lot of progress.
+    pub fn send_with_progress<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> (
+        ProgressStream<TRequest::Progress>,
+        SendFuture<impl Future<Output = Result<TResponse>> + Send + '_>,
+    )
+    where
+        TRequest: ProgressRequest<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded();
+        let context = RequestContext::new().with(ProgressReporter(sender));
+        let response = SendFuture::new(self.send_with_context(request, context));
+        (Box::pin(receiver), response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RequestHandler;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+
+    #[derive(Debug)]
+    pub struct Import(usize);
+
+    impl Request<usize> for Import {}
+
+    impl ProgressRequest<usize> for Import {
+        type Progress = usize;
+    }
+
+    #[derive(Debug)]
+    pub struct ImportHandler;
+
+    #[async_trait]
+    impl RequestHandler<Import, usize> for ImportHandler {
+        async fn handle(&mut self, request: Import) -> Result<usize> {
+            Ok(request.0)
+        }
+
+        async fn handle_with_context(
+            &mut self,
+            request: Import,
+            context: &RequestContext,
+        ) -> Result<usize> {
+            let progress = context.get::<ProgressReporter<usize>>();
+            for i in 1..=request.0 {
+                if let Some(progress) = progress {
+                    progress.report(i);
+                }
+            }
+            Ok(request.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_progress() {
+        let mut m = Mediator::new();
+        m.register_handler(ImportHandler);
+        let (progress, response) = m.send_with_progress(Import(3));
+        let (progress, response) = tokio::join!(progress.collect::<Vec<_>>(), response);
+        assert_eq!(response.unwrap(), 3);
+        assert_eq!(progress, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_progress_dropped_stream() {
+        let mut m = Mediator::new();
+        m.register_handler(ImportHandler);
+        let (progress, response) = m.send_with_progress(Import(3));
+        drop(progress);
+        assert_eq!(response.await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result, SharedMediator};
+
+/// The read handler trait. Unlike the request handler, a read handler only borrows itself,
+/// so it can be dispatched without exclusive access to the mediator.
+///
+/// A `RequestHandler` can be registered as a read handler by wrapping it in a
+/// `futures::lock::Mutex`; the handler is then locked for the duration of each request.
+/// Handlers whose state is already synchronized (atomics, locks, connection pools)
+/// can implement this trait directly instead.
+#[async_trait]
+pub trait ReadHandler<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    async fn handle(&self, request: TRequest) -> Result<TResponse>;
+}
+
+#[async_trait]
+impl<TRequest, TRequestHandler, TResponse> ReadHandler<TRequest, TResponse>
+    for Mutex<TRequestHandler>
+where
+    TRequest: Request<TResponse>,
+    TRequestHandler: RequestHandler<TRequest, TResponse>,
+{
+    async fn handle(&self, request: TRequest) -> Result<TResponse> {
+        self.lock().await.handle(request).await
+    }
+}
+
+impl Mediator {
+    /// Registers a read handler.
+    pub fn register_read_handler<TRequest, TReadHandler, TResponse>(
+        &mut self,
+        handler: TReadHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TReadHandler: ReadHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        self.read_handlers
+            .set::<TRequest, Arc<dyn ReadHandler<TRequest, TResponse>>>(Arc::new(handler));
+        self
+    }
+
+    pub(crate) fn read_handler<TRequest, TResponse>(
+        &self,
+    ) -> Result<Arc<dyn ReadHandler<TRequest, TResponse>>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        match self
+            .read_handlers
+            .get::<TRequest, Arc<dyn ReadHandler<TRequest, TResponse>>>()
+        {
+            Some(h) => Ok(h.clone()),
+            None => Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<TRequest>().into(),
+            ))),
+        }
+    }
+
+    /// Send a request to its read handler.
+    ///
+    /// Read handlers are dispatched without the pipeline behaviors and processors,
+    /// as those require exclusive access to the mediator.
+    pub async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        self.read_handler()?.handle(request).await
+    }
+}
+
+impl SharedMediator {
+    /// Send a request to its read handler.
+    ///
+    /// The mediator is only locked to look up the handler,
+    /// so queries sent from different tasks are handled concurrently.
+    pub async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        let _in_flight = self.in_flight.start()?;
+        let handler = self.lock().await.read_handler()?;
+        handler.handle(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug)]
+    pub struct GetCount;
+
+    impl Request<usize> for GetCount {}
+
+    #[derive(Debug, Default)]
+    pub struct AtomicCountHandler(AtomicUsize);
+
+    #[async_trait]
+    impl ReadHandler<GetCount, usize> for AtomicCountHandler {
+        async fn handle(&self, _request: GetCount) -> Result<usize> {
+            Ok(self.0.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct CountHandler(usize);
+
+    #[async_trait]
+    impl RequestHandler<GetCount, usize> for CountHandler {
+        async fn handle(&mut self, _request: GetCount) -> Result<usize> {
+            self.0 += 1;
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_query() {
+        let mut m = Mediator::new();
+        m.register_read_handler(AtomicCountHandler::default());
+        let m = &m;
+        assert_eq!(m.query(GetCount).await.unwrap(), 1);
+        assert_eq!(m.query(GetCount).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_query_request_handler_behind_mutex() {
+        let mut m = Mediator::new();
+        m.register_read_handler(Mutex::new(CountHandler::default()));
+        assert_eq!(m.query(GetCount).await.unwrap(), 1);
+        assert_eq!(m.query(GetCount).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_query_no_handler_registered() {
+        let m = Mediator::new();
+        let err = m.query(GetCount).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<GetCount>().into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shared_mediator_query_does_not_hold_the_lock() {
+        #[derive(Debug)]
+        pub struct Nested;
+
+        impl Request<usize> for Nested {}
+
+        #[derive(Debug)]
+        pub struct NestedHandler(SharedMediator);
+
+        #[async_trait]
+        impl ReadHandler<Nested, usize> for NestedHandler {
+            async fn handle(&self, _request: Nested) -> Result<usize> {
+                self.0.query(GetCount).await
+            }
+        }
+
+        let shared = SharedMediator::new(Mediator::new());
+        shared
+            .lock()
+            .await
+            .register_read_handler(AtomicCountHandler::default())
+            .register_read_handler(NestedHandler(shared.clone()));
+        assert_eq!(shared.query(Nested).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Mediator, Next, PipelineBehavior, Request, Result};
+
+/// A pipeline behavior recording a copy of every request, e.g. to replay them
+/// with `Mediator::replay` while reproducing an issue.
+///
+/// Every request is kept in memory until it is taken: set a limit for high-throughput
+/// systems, so only the most recent requests are kept. Clones of the behavior share the
+/// same recording.
+pub struct RecordingBehavior<TRequest> {
+    requests: Arc<Mutex<VecDeque<TRequest>>>,
+    limit: Option<usize>,
+}
+
+impl<TRequest> RecordingBehavior<TRequest> {
+    /// Creates a behavior recording every request.
+    pub fn new() -> Self {
+        RecordingBehavior {
+            requests: Arc::new(Mutex::new(VecDeque::new())),
+            limit: None,
+        }
+    }
+
+    /// Only keeps the given number of most recent requests.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns the number of recorded requests.
+    pub fn len(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no request is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes the recorded requests, in the order they were sent.
+    pub fn take(&self) -> Vec<TRequest> {
+        self.requests.lock().unwrap().drain(..).collect()
+    }
+}
+
+impl<TRequest> Default for RecordingBehavior<TRequest> {
+    fn default() -> Self {
+        RecordingBehavior::new()
+    }
+}
+
+impl<TRequest> Clone for RecordingBehavior<TRequest> {
+    fn clone(&self) -> Self {
+        RecordingBehavior {
+            requests: self.requests.clone(),
+            limit: self.limit,
+        }
+    }
+}
+
+impl<TRequest> std::fmt::Debug for RecordingBehavior<TRequest> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingBehavior")
+            .field("requests", &self.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for RecordingBehavior<TRequest>
+where
+    TRequest: Request<TResponse> + Clone,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        {
+            let mut requests = self.requests.lock().unwrap();
+            if self.limit.is_some_and(|limit| requests.len() >= limit) {
+                requests.pop_front();
+            }
+            if self.limit != Some(0) {
+                requests.push_back(request.clone());
+            }
+        }
+        next.run(request).await
+    }
+}
+
+impl Mediator {
+    /// Sends the requests one after the other, e.g. the requests taken from a
+    /// `RecordingBehavior`, and returns their responses.
+    ///
+    /// The replayed requests go through the pipeline, so they are recorded again
+    /// by a recording behavior registered on this mediator.
+    pub async fn replay<TRequest, TResponse, TRequests>(
+        &mut self,
+        requests: TRequests,
+    ) -> Vec<Result<TResponse>>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+        TRequests: IntoIterator<Item = TRequest>,
+    {
+        let mut responses = Vec::new();
+        for request in requests {
+            responses.push(self.send(request).await);
+        }
+        responses
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Double(i64);
+
+    impl Request<i64> for Double {}
+
+    fn mediator(recording: &RecordingBehavior<Double>) -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Double| async move { Ok(request.0 * 2) })
+            .register_behavior_for::<Double, i64, _>(recording.clone());
+        m
+    }
+
+    #[tokio::test]
+    async fn test_recording_behavior_replay() {
+        let recording = RecordingBehavior::new();
+        let mut m = mediator(&recording);
+        for i in 1..=3 {
+            m.send(Double(i)).await.unwrap();
+        }
+        let requests = recording.take();
+        assert_eq!(requests, vec![Double(1), Double(2), Double(3)]);
+        assert!(recording.is_empty());
+
+        let mut replica = Mediator::new();
+        replica.register_handler_fn(|request: Double| async move { Ok(request.0 * 2) });
+        let responses: Vec<_> = replica
+            .replay(requests)
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(responses, vec![2, 4, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_recording_behavior_with_limit() {
+        let recording = RecordingBehavior::new().with_limit(2);
+        let mut m = mediator(&recording);
+        for i in 1..=3 {
+            m.send(Double(i)).await.unwrap();
+        }
+        assert_eq!(recording.take(), vec![Double(2), Double(3)]);
+    }
+}
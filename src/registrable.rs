@@ -0,0 +1,83 @@
+use crate::Mediator;
+
+/// A type that knows how to register itself on a mediator, e.g. a request handler
+/// registering itself for its request type.
+///
+/// This allows building the list of handlers apart from the registration calls,
+/// for instance from the configuration of a modular application.
+pub trait Registrable {
+    /// Registers this item on the mediator.
+    fn register(self: Box<Self>, mediator: &mut Mediator);
+}
+
+impl Mediator {
+    /// Registers each of the items.
+    pub fn register_all<TItems>(&mut self, items: TItems) -> &mut Self
+    where
+        TItems: IntoIterator<Item = Box<dyn Registrable>>,
+    {
+        for item in items {
+            item.register(self);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Request, RequestHandler, Result};
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug)]
+    pub struct Add(i64, i64);
+
+    impl Request<i64> for Add {}
+
+    #[derive(Debug)]
+    pub struct PingHandler;
+
+    #[async_trait]
+    impl RequestHandler<Ping, String> for PingHandler {
+        async fn handle(&mut self, _request: Ping) -> Result<String> {
+            Ok(String::from("pong!"))
+        }
+    }
+
+    impl Registrable for PingHandler {
+        fn register(self: Box<Self>, mediator: &mut Mediator) {
+            mediator.register_handler(*self);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AddHandler;
+
+    #[async_trait]
+    impl RequestHandler<Add, i64> for AddHandler {
+        async fn handle(&mut self, request: Add) -> Result<i64> {
+            Ok(request.0 + request.1)
+        }
+    }
+
+    impl Registrable for AddHandler {
+        fn register(self: Box<Self>, mediator: &mut Mediator) {
+            mediator.register_handler(*self);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_all() {
+        let items: Vec<Box<dyn Registrable>> = vec![Box::new(PingHandler), Box::new(AddHandler)];
+        let mut m = Mediator::new();
+        m.register_all(items);
+        assert_eq!(m.handler_count(), 2);
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+        assert_eq!(m.send(Add(1, 2)).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use std::{any::Any, future::Future};
+use uuid::Uuid;
+
+use crate::{
+    AnyNext, GlobalPipelineBehavior, Mediator, Request, RequestContext, Result, SendFuture,
+};
+
+/// A unique id of a request, to correlate everything logged while it is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RequestId(Uuid);
+
+impl RequestId {
+    /// Generates a random request id.
+    pub fn random() -> Self {
+        RequestId(Uuid::new_v4())
+    }
+
+    /// Returns the id as a UUID.
+    pub fn uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl From<Uuid> for RequestId {
+    fn from(uuid: Uuid) -> Self {
+        RequestId(uuid)
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A global pipeline behavior that gives every request an id,
+/// available to the later behaviors and to the handler through `RequestContext::request_id`.
+///
+/// The id set by the caller with `RequestContext::with_request_id` is kept,
+/// otherwise a random one is generated. With the `log` feature, the id is logged at the
+/// debug level when the request is handled.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RequestIdBehavior;
+
+impl RequestIdBehavior {
+    /// Creates a new request id behavior.
+    pub fn new() -> Self {
+        RequestIdBehavior
+    }
+}
+
+#[async_trait]
+impl GlobalPipelineBehavior for RequestIdBehavior {
+    async fn handle(
+        &mut self,
+        request: Box<dyn Any + Send>,
+        mut next: AnyNext<'_>,
+    ) -> Result<Box<dyn Any + Send>> {
+        let _id = next.context().request_id_or_random();
+        #[cfg(feature = "log")]
+        log::debug!("Handling {} as request {}", next.request_type_name(), _id);
+        next.run(request).await
+    }
+}
+
+impl Mediator {
+    /// Send a request to the mediator with a random request id,
+    /// which is returned so the caller can correlate the request.
+    ///
+    /// The id is available through `RequestContext::request_id`, also without the
+    /// `RequestIdBehavior`.
+    pub fn send_with_request_id<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> (
+        RequestId,
+        SendFuture<impl Future<Output = Result<TResponse>> + Send + '_>,
+    )
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let id = RequestId::random();
+        let context = RequestContext::new().with_request_id(id);
+        (
+            id,
+            SendFuture::new(self.send_with_context(request, context)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RequestHandler;
+
+    #[derive(Debug)]
+    pub struct WhatIsMyId;
+
+    impl Request<Option<RequestId>> for WhatIsMyId {}
+
+    #[derive(Debug)]
+    pub struct WhatIsMyIdHandler;
+
+    #[async_trait]
+    impl RequestHandler<WhatIsMyId, Option<RequestId>> for WhatIsMyIdHandler {
+        async fn handle(&mut self, _request: WhatIsMyId) -> Result<Option<RequestId>> {
+            Ok(None)
+        }
+
+        async fn handle_with_context(
+            &mut self,
+            _request: WhatIsMyId,
+            context: &RequestContext,
+        ) -> Result<Option<RequestId>> {
+            Ok(context.request_id())
+        }
+    }
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler(WhatIsMyIdHandler)
+            .register_behavior(RequestIdBehavior::new());
+        m
+    }
+
+    #[tokio::test]
+    async fn test_request_id_behavior_generates_ids() {
+        let mut m = mediator();
+        let first = m.send(WhatIsMyId).await.unwrap();
+        let second = m.send(WhatIsMyId).await.unwrap();
+        assert!(first.is_some());
+        assert!(second.is_some());
+        assert_ne!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_request_id() {
+        let mut m = mediator();
+        let (id, response) = m.send_with_request_id(WhatIsMyId);
+        assert_eq!(response.await.unwrap(), Some(id));
+        let id = RequestId::from(Uuid::nil());
+        let context = RequestContext::new().with_request_id(id);
+        let response = m.send_with_context(WhatIsMyId, context).await.unwrap();
+        assert_eq!(response, Some(id));
+    }
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use std::{error::Error, time::Duration};
+
+use crate::{Next, PipelineBehavior, Request, Result};
+
+type RetryPredicate = Box<dyn Fn(&(dyn Error + 'static)) -> bool + Send + Sync>;
+
+/// A pipeline behavior that retries failed requests.
+///
+/// The request is cloned before every attempt, and the last error is returned
+/// once every retry is exhausted. Waiting between attempts relies on the runtime timer.
+pub struct RetryBehavior {
+    max_retries: usize,
+    backoff: Duration,
+    predicate: RetryPredicate,
+}
+
+impl RetryBehavior {
+    /// Creates a behavior retrying a failed request up to `max_retries` times,
+    /// waiting for `backoff` between attempts.
+    pub fn new(max_retries: usize, backoff: Duration) -> Self {
+        RetryBehavior {
+            max_retries,
+            backoff,
+            predicate: Box::new(|_| true),
+        }
+    }
+
+    /// Only retries the errors for which the predicate returns `true`.
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&(dyn Error + 'static)) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Box::new(predicate);
+        self
+    }
+}
+
+impl std::fmt::Debug for RetryBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryBehavior")
+            .field("max_retries", &self.max_retries)
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for RetryBehavior
+where
+    TRequest: Request<TResponse> + Clone,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let mut retries = 0;
+        loop {
+            match next.run(request.clone()).await {
+                Err(e) if retries < self.max_retries && (self.predicate)(e.as_ref()) => {
+                    retries += 1
+                }
+                result => return result,
+            }
+            crate::runtime::sleep(self.backoff).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug, Clone)]
+    pub struct Flaky;
+
+    impl Request<usize> for Flaky {}
+
+    /// Registers a handler failing the given number of times, and returns its attempt counter.
+    fn mediator(failures: usize, behavior: RetryBehavior) -> (Mediator, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let mut m = Mediator::new();
+        m.register_handler_fn(move |_: Flaky| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt <= failures {
+                    Err(std::fmt::Error.into())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .register_behavior_for::<Flaky, usize, _>(behavior);
+        (m, attempts)
+    }
+
+    #[tokio::test]
+    async fn test_retry_behavior_succeeds_after_retries() {
+        let (mut m, attempts) = mediator(2, RetryBehavior::new(3, Duration::from_millis(1)));
+        assert_eq!(m.send(Flaky).await.unwrap(), 3);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_behavior_exhausted() {
+        let (mut m, attempts) = mediator(5, RetryBehavior::new(2, Duration::from_millis(1)));
+        assert!(m.send(Flaky).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_behavior_predicate() {
+        let behavior = RetryBehavior::new(3, Duration::from_millis(1))
+            .retry_if(|e| !e.is::<std::fmt::Error>());
+        let (mut m, attempts) = mediator(1, behavior);
+        assert!(m.send(Flaky).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
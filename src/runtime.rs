@@ -0,0 +1,65 @@
+//! The timer of the async runtime, used by the retry and timeout helpers.
+//!
+//! The tokio timer is used when both the `tokio` and the `async-std` features are enabled.
+//! When neither is, every timer runs on a thread of its own, which works with any runtime.
+
+#[cfg(feature = "timeout")]
+use std::future::Future;
+use std::time::Duration;
+
+/// Waits until the duration has elapsed.
+#[cfg(all(feature = "retry", feature = "tokio"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Waits until the duration has elapsed.
+#[cfg(all(feature = "retry", feature = "async-std", not(feature = "tokio")))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await
+}
+
+/// Waits until the duration has elapsed.
+#[cfg(all(feature = "retry", not(any(feature = "tokio", feature = "async-std"))))]
+pub(crate) async fn sleep(duration: Duration) {
+    thread_timer(duration).await
+}
+
+/// Runs the future, returning `None` if it does not complete within the duration.
+#[cfg(all(feature = "timeout", feature = "tokio"))]
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    tokio::time::timeout(duration, future).await.ok()
+}
+
+/// Runs the future, returning `None` if it does not complete within the duration.
+#[cfg(all(feature = "timeout", feature = "async-std", not(feature = "tokio")))]
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    async_std::future::timeout(duration, future).await.ok()
+}
+
+/// Runs the future, returning `None` if it does not complete within the duration.
+#[cfg(all(
+    feature = "timeout",
+    not(any(feature = "tokio", feature = "async-std"))
+))]
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    use futures::future::{select, Either};
+
+    futures::pin_mut!(future);
+    match select(future, Box::pin(thread_timer(duration))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+/// Waits until the duration has elapsed on a thread spawned for the timer, which keeps
+/// running until then even if the future waiting for it is dropped.
+#[cfg(not(any(feature = "tokio", feature = "async-std")))]
+async fn thread_timer(duration: Duration) {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
@@ -0,0 +1,160 @@
+use crate::{Mediator, ReadHandler, Request, RequestHandler, Result};
+
+/// A mediator scoped to a parent mediator, e.g. for the lifetime of an incoming request.
+///
+/// Handlers registered on the scope shadow the handlers of the parent for this scope only,
+/// and the requests without a handler in the scope are sent to the parent. The parent is
+/// borrowed exclusively, as its request handlers need it, and is left unchanged once the
+/// scope is dropped.
+#[derive(Debug)]
+pub struct ScopedMediator<'a> {
+    parent: &'a mut Mediator,
+    local: Mediator,
+}
+
+impl Mediator {
+    /// Creates a new scope inheriting the handlers of this mediator.
+    pub fn scope(&mut self) -> ScopedMediator<'_> {
+        ScopedMediator {
+            parent: self,
+            local: Mediator::new(),
+        }
+    }
+}
+
+impl ScopedMediator<'_> {
+    /// Registers a request handler for this scope.
+    pub fn register_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.local.register_handler(handler);
+        self
+    }
+
+    /// Registers a read handler for this scope.
+    pub fn register_read_handler<TRequest, TReadHandler, TResponse>(
+        &mut self,
+        handler: TReadHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TReadHandler: ReadHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        self.local.register_read_handler(handler);
+        self
+    }
+
+    /// Send a request to the request handler of this scope, falling back to the read handler
+    /// of this scope, then to the request handler of the parent, then to its read handler.
+    pub async fn send<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        if self.local.has_handler::<TRequest, TResponse>() {
+            return self.local.send(request).await;
+        }
+        if let Ok(handler) = self.local.read_handler::<TRequest, TResponse>() {
+            return handler.handle(request).await;
+        }
+        if self.parent.has_handler::<TRequest, TResponse>() {
+            return self.parent.send(request).await;
+        }
+        self.parent.query(request).await
+    }
+
+    /// Send a request to the read handler of this scope, falling back to the parent's.
+    pub async fn query<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        match self.local.read_handler::<TRequest, TResponse>() {
+            Ok(handler) => handler.handle(request).await,
+            Err(_) => self.parent.query(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MediatorError;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Tenant;
+
+    impl Request<String> for Tenant {}
+
+    #[derive(Debug)]
+    pub struct TenantHandler(&'static str);
+
+    #[async_trait]
+    impl ReadHandler<Tenant, String> for TenantHandler {
+        async fn handle(&self, _request: Tenant) -> Result<String> {
+            Ok(String::from(self.0))
+        }
+    }
+
+    #[async_trait]
+    impl RequestHandler<Tenant, String> for TenantHandler {
+        async fn handle(&mut self, _request: Tenant) -> Result<String> {
+            Ok(String::from(self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scoped_mediator_falls_back_to_parent() {
+        let mut m = Mediator::new();
+        m.register_read_handler(TenantHandler("global"));
+        let mut scope = m.scope();
+        assert_eq!(scope.send(Tenant).await.unwrap(), "global");
+        assert_eq!(scope.query(Tenant).await.unwrap(), "global");
+    }
+
+    #[tokio::test]
+    async fn test_scoped_mediator_falls_back_to_parent_request_handler() {
+        let mut m = Mediator::new();
+        m.register_handler(TenantHandler("global"));
+        let mut scope = m.scope();
+        assert_eq!(scope.send(Tenant).await.unwrap(), "global");
+        scope.register_handler(TenantHandler("scoped"));
+        assert_eq!(scope.send(Tenant).await.unwrap(), "scoped");
+        drop(scope);
+        assert_eq!(m.send(Tenant).await.unwrap(), "global");
+    }
+
+    #[tokio::test]
+    async fn test_scoped_mediator_shadows_parent() {
+        let mut m = Mediator::new();
+        m.register_read_handler(TenantHandler("global"));
+        {
+            let mut scope = m.scope();
+            scope.register_read_handler(TenantHandler("read"));
+            assert_eq!(scope.query(Tenant).await.unwrap(), "read");
+            scope.register_handler(TenantHandler("scoped"));
+            assert_eq!(scope.send(Tenant).await.unwrap(), "scoped");
+        }
+        assert_eq!(m.query(Tenant).await.unwrap(), "global");
+    }
+
+    #[tokio::test]
+    async fn test_scoped_mediator_no_handler_registered() {
+        let mut m = Mediator::new();
+        let err = m.scope().send(Tenant).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Tenant>().into()
+            ))
+        );
+    }
+}
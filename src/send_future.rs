@@ -0,0 +1,114 @@
+use pin_project_lite::pin_project;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::Result;
+
+pin_project! {
+    /// The pending response to a request sent to the mediator.
+    ///
+    /// It is a future, polled like the future it wraps, and offers combinators to transform
+    /// the response before awaiting it, e.g. `mediator.send(request).map(|r| r.len()).await?`.
+    #[must_use = "requests are not sent until awaited"]
+    pub struct SendFuture<TFuture> {
+        #[pin]
+        future: TFuture,
+    }
+}
+
+impl<TFuture, TResponse> SendFuture<TFuture>
+where
+    TFuture: Future<Output = Result<TResponse>>,
+{
+    pub(crate) fn new(future: TFuture) -> Self {
+        SendFuture { future }
+    }
+
+    /// Maps the response, leaving an error untouched.
+    pub fn map<TMapped, F>(self, f: F) -> SendFuture<impl Future<Output = Result<TMapped>>>
+    where
+        F: FnOnce(TResponse) -> TMapped,
+    {
+        SendFuture::new(async move { self.future.await.map(f) })
+    }
+
+    /// Fails with `MediatorError::TimeoutError` if the response is not received
+    /// within the given duration.
+    #[cfg(feature = "timeout")]
+    pub fn timeout(
+        self,
+        duration: std::time::Duration,
+    ) -> SendFuture<impl Future<Output = Result<TResponse>>> {
+        SendFuture::new(crate::timeout::timeout(duration, self.future))
+    }
+}
+
+impl<TFuture> Future for SendFuture<TFuture>
+where
+    TFuture: Future,
+{
+    type Output = TFuture::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx)
+    }
+}
+
+impl<TFuture> std::fmt::Debug for SendFuture<TFuture> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendFuture").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Mediator, Request};
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[tokio::test]
+    async fn test_send_future_map() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        assert_eq!(m.send(Ping).map(|r| r.len()).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_send_future_is_a_future() {
+        use futures::FutureExt;
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        let response = m.send(Ping).now_or_never().unwrap();
+        assert_eq!(response.unwrap(), "pong!");
+    }
+
+    #[cfg(feature = "timeout")]
+    #[tokio::test]
+    async fn test_send_future_timeout() {
+        use crate::MediatorError;
+        use std::time::Duration;
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|_: Ping| async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(String::from("pong!"))
+        });
+        let err = m
+            .send(Ping)
+            .timeout(Duration::from_millis(10))
+            .map(|r| r.len())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::TimeoutError)
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use serde::de::DeserializeOwned;
+use std::{any::Any, collections::HashMap};
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result};
+
+/// A function deserializing the bytes of a request into the request, type-erased.
+pub type Deserializer = fn(&[u8]) -> Result<Box<dyn Any + Send>>;
+
+/// A request which can be sent serialized as JSON, e.g. over a network or a message bus.
+pub trait SerializableRequest<TResponse>: Request<TResponse> + DeserializeOwned {
+    /// The name the request is sent under.
+    ///
+    /// It has to be stable across the builds of the sender and the receiver,
+    /// which `std::any::type_name` is not.
+    const TYPE_NAME: &'static str;
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Deserializers(HashMap<String, Deserializer>);
+
+fn deserialize_json<TRequest>(bytes: &[u8]) -> Result<Box<dyn Any + Send>>
+where
+    TRequest: DeserializeOwned + Send + 'static,
+{
+    Ok(Box::new(serde_json::from_slice::<TRequest>(bytes)?))
+}
+
+impl Mediator {
+    /// Registers the deserializer of the requests sent serialized under the given type name.
+    ///
+    /// The deserialized request is sent to the named handler registered under the type name.
+    /// Registering another deserializer under the same name replaces the previous one.
+    pub fn register_deserializer(
+        &mut self,
+        type_name: &str,
+        deserializer: Deserializer,
+    ) -> &mut Self {
+        self.deserializers
+            .0
+            .insert(type_name.to_string(), deserializer);
+        self
+    }
+
+    /// Registers a request handler for the request sent serialized as JSON under its type name.
+    pub fn register_serializable_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: SerializableRequest<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_deserializer(TRequest::TYPE_NAME, deserialize_json::<TRequest>)
+            .register_named_handler(TRequest::TYPE_NAME, handler)
+    }
+
+    /// Send a serialized request to the handler registered under the given type name.
+    ///
+    /// Returns `MediatorError::HandlerNotRegisteredError` if no deserializer is registered under
+    /// the type name. The response has to be downcast by the caller.
+    ///
+    /// # Security
+    ///
+    /// The type name and the bytes usually come from outside the process, so both are untrusted.
+    /// Only the requests whose deserializer is registered can be sent, which makes the registered
+    /// type names an allowlist: never register a request that is not meant to be sent remotely.
+    /// The deserializer runs before any pipeline behavior, e.g. before authorization, and bounds
+    /// on the size of the bytes have to be enforced by the transport.
+    pub async fn send_serialized(
+        &mut self,
+        bytes: &[u8],
+        type_name: &str,
+    ) -> Result<Box<dyn Any + Send>> {
+        let request = match self.deserializers.0.get(type_name) {
+            Some(deserialize) => deserialize(bytes)?,
+            None => {
+                return Err(Box::new(MediatorError::HandlerNotRegisteredError(
+                    type_name.to_string().into(),
+                )))
+            }
+        };
+        self.send_named(type_name, request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Greet {
+        name: String,
+    }
+
+    impl Request<String> for Greet {}
+
+    impl SerializableRequest<String> for Greet {
+        const TYPE_NAME: &'static str = "greet";
+    }
+
+    #[derive(Debug)]
+    pub struct GreetHandler;
+
+    #[async_trait::async_trait]
+    impl RequestHandler<Greet, String> for GreetHandler {
+        async fn handle(&mut self, request: Greet) -> Result<String> {
+            Ok(format!("Hello, {}!", request.name))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_serialized() {
+        let mut m = Mediator::new();
+        m.register_serializable_handler(GreetHandler);
+        let response = m
+            .send_serialized(br#"{"name":"world"}"#, "greet")
+            .await
+            .unwrap();
+        assert_eq!(
+            response.downcast_ref::<String>().map(String::as_str),
+            Some("Hello, world!")
+        );
+        assert!(m.send_serialized(b"not json", "greet").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_serialized_unknown_type_name() {
+        let mut m = Mediator::new();
+        m.register_serializable_handler(GreetHandler);
+        let err = m
+            .send_serialized(br#"{"name":"world"}"#, "Greet")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError("Greet".into()))
+        );
+    }
+}
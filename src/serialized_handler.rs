@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{Mediator, MediatorError, Request, RequestHandler, Result};
+
+enum Message<TRequest, TResponse> {
+    Request(TRequest, oneshot::Sender<Result<TResponse>>),
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// A request handler running on its own task, which handles the requests queued by every
+/// clone of the serialized handler one at a time, in arrival order.
+///
+/// Clones of a serialized handler can be registered on several mediators, e.g. one mediator
+/// per task, so the requests sent concurrently through all of them are still handled in order.
+/// A request is handled once queued even if its `send` is cancelled, so it is never abandoned
+/// halfway, e.g. after applying half of an event.
+///
+/// Every request goes through the queue and a task switch, and waits for the requests queued
+/// before it: the throughput of a request type is that of its slowest request, and a
+/// multithreaded runtime does not handle its requests any faster. The queue is unbounded,
+/// but a caller waits for its response, so it holds at most one request per concurrent caller.
+/// The context of the request is not passed to the handler, which runs on another task.
+pub struct SerializedHandler<TRequest, TResponse> {
+    sender: mpsc::UnboundedSender<Message<TRequest, TResponse>>,
+}
+
+impl<TRequest, TResponse> SerializedHandler<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    /// Spawns the task running the handler, which stops once every clone is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn spawn<TRequestHandler>(mut handler: TRequestHandler) -> Self
+    where
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Message::Request(request, responder) => {
+                        let _ = responder.send(handler.handle(request).await);
+                    }
+                    Message::Shutdown(done) => {
+                        handler.on_shutdown().await;
+                        let _ = done.send(());
+                        return;
+                    }
+                }
+            }
+        });
+        SerializedHandler { sender }
+    }
+}
+
+impl<TRequest, TResponse> Clone for SerializedHandler<TRequest, TResponse> {
+    fn clone(&self) -> Self {
+        SerializedHandler {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<TRequest, TResponse> std::fmt::Debug for SerializedHandler<TRequest, TResponse> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerializedHandler")
+            .field("closed", &self.sender.is_closed())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for SerializedHandler<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+    TResponse: Send + 'static,
+{
+    /// Queues the request and waits for its response.
+    ///
+    /// Fails with `MediatorError::HandlerUnavailableError` once the handler is shut down,
+    /// or if it panicked.
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Message::Request(request, responder))
+            .map_err(|_| MediatorError::HandlerUnavailableError)?;
+        response
+            .await
+            .map_err(|_| MediatorError::HandlerUnavailableError)?
+    }
+
+    /// Shuts the handler down once the requests queued before are handled,
+    /// for every clone of the serialized handler.
+    async fn on_shutdown(&mut self) {
+        let (done, shut_down) = oneshot::channel();
+        if self.sender.send(Message::Shutdown(done)).is_ok() {
+            let _ = shut_down.await;
+        }
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler handling the requests one at a time, in arrival order,
+    /// e.g. to apply the events of an aggregate strictly in order.
+    ///
+    /// The handler runs on its own task. See the `SerializedHandler` for the throughput
+    /// implications, and to share the handler across mediators.
+    ///
+    /// Unlike the other handlers, dropping the future returned by `send` does not cancel
+    /// the request once it is queued: the handler still handles it in turn, and its response
+    /// is discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn register_serialized_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_handler(SerializedHandler::spawn(handler))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[derive(Debug)]
+    pub struct Apply(u32);
+
+    impl Request<usize> for Apply {}
+
+    #[derive(Debug, Default)]
+    pub struct EventLog {
+        applied: Arc<Mutex<Vec<u32>>>,
+        in_flight: Arc<Mutex<(usize, usize)>>,
+    }
+
+    #[async_trait]
+    impl RequestHandler<Apply, usize> for EventLog {
+        async fn handle(&mut self, request: Apply) -> Result<usize> {
+            {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                in_flight.0 += 1;
+                in_flight.1 = in_flight.1.max(in_flight.0);
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.in_flight.lock().unwrap().0 -= 1;
+            let mut applied = self.applied.lock().unwrap();
+            applied.push(request.0);
+            Ok(applied.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serialized_handler_in_arrival_order() {
+        let log = EventLog::default();
+        let (applied, in_flight) = (log.applied.clone(), log.in_flight.clone());
+        let handler = SerializedHandler::spawn(log);
+        let tasks: Vec<_> = (0..5)
+            .map(|i| {
+                let mut m = Mediator::new();
+                m.register_handler(handler.clone());
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(u64::from(i) * 2)).await;
+                    m.send(Apply(i)).await.unwrap()
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*applied.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(in_flight.lock().unwrap().1, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_serialized_handler_cancelled_send() {
+        let log = EventLog::default();
+        let applied = log.applied.clone();
+        let mut m = Mediator::new();
+        m.register_serialized_handler(log);
+        let cancelled = tokio::time::timeout(Duration::from_millis(1), m.send(Apply(1))).await;
+        assert!(cancelled.is_err());
+        assert_eq!(m.send(Apply(2)).await.unwrap(), 2);
+        assert_eq!(*applied.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_serialized_handler_shutdown() {
+        let mut handler = SerializedHandler::spawn(EventLog::default());
+        let mut m = Mediator::new();
+        m.register_handler(handler.clone());
+        assert_eq!(m.send(Apply(1)).await.unwrap(), 1);
+        m.shutdown().await;
+        let err = handler.handle(Apply(2)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerUnavailableError)
+        );
+    }
+}
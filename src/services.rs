@@ -0,0 +1,133 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+use crate::{Mediator, RequestContext};
+
+/// The services registered on a mediator, e.g. a database pool or an HTTP client,
+/// resolved by the handlers through `RequestContext::resolve`.
+///
+/// The provider holds at most one service of each type. Services are shared with every
+/// request rather than stored in each handler, so they must be `Send + Sync + 'static`:
+/// a service borrowing data must own it or hold it behind an `Arc` instead.
+#[derive(Clone, Default)]
+pub struct ServiceProvider(HashMap<TypeId, Arc<dyn Any + Send + Sync>>);
+
+impl ServiceProvider {
+    /// Creates a new empty service provider.
+    pub fn new() -> Self {
+        ServiceProvider::default()
+    }
+
+    /// Registers a service, replacing the service of the same type.
+    pub fn register<T: Any + Send + Sync>(&mut self, service: T) {
+        self.0.insert(TypeId::of::<T>(), Arc::new(service));
+    }
+
+    /// Returns the service of the given type.
+    pub fn resolve<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.clone().downcast::<T>().ok())
+    }
+
+    /// Returns the number of registered services.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no service is registered.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Debug for ServiceProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceProvider")
+            .field("services", &self.0.len())
+            .finish()
+    }
+}
+
+impl Mediator {
+    /// Registers a service, replacing the service of the same type,
+    /// which the handlers resolve from the context with `RequestContext::resolve`.
+    ///
+    /// Requests already in flight keep resolving the services registered when they were sent.
+    pub fn register_service<T: Any + Send + Sync>(&mut self, service: T) -> &mut Self {
+        Arc::make_mut(&mut self.services).register(service);
+        self
+    }
+
+    /// Gives the context the services of the mediator, unless it already has a provider.
+    pub(crate) fn provide_services(&self, context: &RequestContext) {
+        if !self.services.is_empty() {
+            context.set_services(self.services.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Request, RequestHandler, Result};
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Database(&'static str);
+
+    #[derive(Debug)]
+    pub struct GetUser(u32);
+
+    impl Request<String> for GetUser {}
+
+    #[derive(Debug)]
+    pub struct GetUserHandler;
+
+    #[async_trait]
+    impl RequestHandler<GetUser, String> for GetUserHandler {
+        async fn handle(&mut self, request: GetUser) -> Result<String> {
+            self.handle_with_context(request, &RequestContext::new())
+                .await
+        }
+
+        async fn handle_with_context(
+            &mut self,
+            request: GetUser,
+            context: &RequestContext,
+        ) -> Result<String> {
+            let database = context
+                .resolve::<Database>()
+                .ok_or("no database registered")?;
+            Ok(format!("user {} from {}", request.0, database.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_service() {
+        let mut m = Mediator::new();
+        m.register_handler(GetUserHandler);
+        assert_eq!(
+            m.send(GetUser(1)).await.unwrap_err().to_string(),
+            "no database registered"
+        );
+        m.register_service(Database("primary"));
+        assert_eq!(m.send(GetUser(1)).await.unwrap(), "user 1 from primary");
+        m.register_service(Database("replica"));
+        assert_eq!(m.send(GetUser(2)).await.unwrap(), "user 2 from replica");
+    }
+
+    #[test]
+    fn test_service_provider_resolve() {
+        let mut services = ServiceProvider::new();
+        services.register(Database("primary"));
+        services.register(42u32);
+        assert_eq!(services.len(), 2);
+        assert_eq!(services.resolve::<Database>().unwrap().0, "primary");
+        assert_eq!(services.resolve::<u32>().as_deref(), Some(&42));
+        assert!(services.resolve::<u64>().is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use futures::lock::{Mutex, MutexGuard};
+use std::sync::Arc;
+
+use crate::{drain::InFlight, Mediator, Notification, Request, Result};
+
+/// A mediator that can be cloned and shared across tasks.
+///
+/// Every clone dispatches to the same handlers. Handlers take `&mut self`,
+/// so the mediator is held behind a single lock for the whole duration of a request:
+/// requests sent from different tasks are handled one at a time.
+#[derive(Clone, Debug)]
+pub struct SharedMediator {
+    mediator: Arc<Mutex<Mediator>>,
+    pub(crate) in_flight: Arc<InFlight>,
+}
+
+impl SharedMediator {
+    /// Creates a new shared mediator from a mediator.
+    pub fn new(mediator: Mediator) -> Self {
+        SharedMediator {
+            mediator: Arc::new(Mutex::new(mediator)),
+            in_flight: Arc::default(),
+        }
+    }
+
+    /// Locks the underlying mediator, e.g. to register additional handlers.
+    pub async fn lock(&self) -> MutexGuard<'_, Mediator> {
+        self.mediator.lock().await
+    }
+
+    /// Send a request to the mediator.
+    pub async fn send<TRequest, TResponse>(&self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let _in_flight = self.in_flight.start()?;
+        self.mediator.lock().await.send(request).await
+    }
+
+    /// Publish a notification to every registered handler.
+    pub async fn publish<TNotification>(&self, notification: TNotification) -> Result<()>
+    where
+        TNotification: Notification,
+    {
+        let _in_flight = self.in_flight.start()?;
+        self.mediator.lock().await.publish(notification).await
+    }
+}
+
+impl From<Mediator> for SharedMediator {
+    fn from(mediator: Mediator) -> Self {
+        SharedMediator::new(mediator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RequestHandler;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct CountRequest;
+
+    impl Request<usize> for CountRequest {}
+
+    #[derive(Debug, Default)]
+    pub struct CountRequestHandler(usize);
+
+    #[async_trait]
+    impl RequestHandler<CountRequest, usize> for CountRequestHandler {
+        async fn handle(&mut self, _request: CountRequest) -> Result<usize> {
+            self.0 += 1;
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_mediator_send_from_tasks() {
+        let mut m = Mediator::new();
+        m.register_handler(CountRequestHandler::default());
+        let shared = SharedMediator::new(m);
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let shared = shared.clone();
+                tokio::spawn(async move { shared.send(CountRequest).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(shared.send(CountRequest).await.unwrap(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_shared_mediator_register_after_sharing() {
+        let shared = SharedMediator::from(Mediator::new());
+        assert!(shared.send(CountRequest).await.is_err());
+        shared
+            .lock()
+            .await
+            .register_handler(CountRequestHandler::default());
+        assert_eq!(shared.send(CountRequest).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+use crate::{Mediator, Request, RequestContext, RequestHandler, Result};
+
+#[async_trait]
+impl<TRequest, TRequestHandler, TResponse> RequestHandler<TRequest, TResponse>
+    for Arc<Mutex<TRequestHandler>>
+where
+    TRequest: Request<TResponse>,
+    TRequestHandler: RequestHandler<TRequest, TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        self.lock().await.handle(request).await
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.lock()
+            .await
+            .handle_with_context(request, context)
+            .await
+    }
+
+    async fn on_shutdown(&mut self) {
+        self.lock().await.on_shutdown().await
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler shared with the caller, who keeps a clone of it,
+    /// e.g. to inspect its state after requests were sent.
+    ///
+    /// The handler is locked for the duration of each request, so locking it from outside
+    /// waits for the request being handled to complete. Holding the lock while sending a
+    /// request to the handler deadlocks.
+    pub fn register_shared_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: Arc<Mutex<TRequestHandler>>,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_handler(handler)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug, Default)]
+    pub struct CountingHandler {
+        calls: usize,
+    }
+
+    #[async_trait]
+    impl RequestHandler<Ping, String> for CountingHandler {
+        async fn handle(&mut self, _request: Ping) -> Result<String> {
+            self.calls += 1;
+            Ok(String::from("pong!"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_shared_handler() {
+        let handler = Arc::new(Mutex::new(CountingHandler::default()));
+        let mut m = Mediator::new();
+        m.register_shared_handler(handler.clone());
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+        assert_eq!(m.send(Ping).await.unwrap(), "pong!");
+        assert_eq!(handler.lock().await.calls, 2);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::Mediator;
+
+impl Mediator {
+    /// Shuts the mediator down, calling `on_shutdown` on the request handlers, the keyed
+    /// handlers and the named handlers before dropping them, e.g. so handlers holding
+    /// connections can close them cleanly.
+    ///
+    /// The handlers are shut down one after another, in no particular order. The handlers
+    /// wrapping other handlers, e.g. the conditional or lazy handlers, shut down the handlers
+    /// they wrap. A shared handler is shut down even if the caller keeps a clone of it.
+    ///
+    /// The other handlers, e.g. the notification, stream or sync handlers, are dropped without
+    /// being shut down. A request type is shut down for the response type it was last
+    /// registered with: after registering handlers for a request with two response types,
+    /// e.g. a chainable handler and then a handler, only the last one is shut down.
+    pub async fn shutdown(mut self) {
+        let shutdowns: Vec<_> = self
+            .handler_infos
+            .values()
+            .map(|info| info.shutdown)
+            .chain(self.keyed_shutdowns.values().copied())
+            .collect();
+        for shutdown in shutdowns {
+            shutdown(&mut self).await;
+        }
+        self.named_handlers.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Request, RequestHandler, Result};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    pub struct Write(&'static str);
+
+    impl Request<()> for Write {}
+
+    #[derive(Debug)]
+    pub struct BufferedWriter {
+        buffer: Vec<&'static str>,
+        flushed: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl RequestHandler<Write, ()> for BufferedWriter {
+        async fn handle(&mut self, request: Write) -> Result<()> {
+            self.buffer.push(request.0);
+            Ok(())
+        }
+
+        async fn on_shutdown(&mut self) {
+            self.flushed.lock().unwrap().append(&mut self.buffer);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[tokio::test]
+    async fn test_mediator_shutdown() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let mut m = Mediator::new();
+        m.register_handler(BufferedWriter {
+            buffer: Vec::new(),
+            flushed: flushed.clone(),
+        })
+        .register_handler_fn(|_: Ping| async { Ok(String::from("pong!")) });
+        m.send(Write("a")).await.unwrap();
+        m.send(Write("b")).await.unwrap();
+        assert!(flushed.lock().unwrap().is_empty());
+        m.shutdown().await;
+        assert_eq!(*flushed.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_shutdown_keyed_and_named_handlers() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let writer = |flushed: &Arc<Mutex<Vec<_>>>| BufferedWriter {
+            buffer: Vec::new(),
+            flushed: flushed.clone(),
+        };
+        let mut m = Mediator::new();
+        m.register_keyed_handler("first", writer(&flushed))
+            .register_keyed_handler("second", writer(&flushed))
+            .register_named_handler("write", writer(&flushed));
+        m.send_keyed("first", Write("a")).await.unwrap();
+        m.send_keyed("second", Write("b")).await.unwrap();
+        m.send_named("write", Box::new(Write("c"))).await.unwrap();
+        m.shutdown().await;
+        let mut flushed = flushed.lock().unwrap().clone();
+        flushed.sort_unstable();
+        assert_eq!(flushed, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_shutdown_conditional_handlers() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let writer = |flushed: &Arc<Mutex<Vec<_>>>| BufferedWriter {
+            buffer: Vec::new(),
+            flushed: flushed.clone(),
+        };
+        let mut m = Mediator::new();
+        m.register_handler_if(|request: &Write| request.0 == "a", writer(&flushed))
+            .register_handler_if(|_: &Write| true, writer(&flushed));
+        m.send(Write("a")).await.unwrap();
+        m.send(Write("b")).await.unwrap();
+        m.shutdown().await;
+        assert_eq!(*flushed.lock().unwrap(), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+
+use crate::{Mediator, Result};
+
+/// The stream request trait. A stream request produces many items over time.
+pub trait StreamRequest<TItem>: Send + 'static {}
+
+/// The stream returned by a stream request handler.
+pub type ResponseStream<TItem> = Pin<Box<dyn Stream<Item = Result<TItem>> + Send>>;
+
+/// The stream request handler trait. The handler is responsible for creating the stream of items.
+#[async_trait]
+pub trait StreamRequestHandler<TRequest, TItem>: Send + Sync
+where
+    TRequest: StreamRequest<TItem>,
+{
+    /// The method that handles the request.
+    async fn handle(&mut self, request: TRequest) -> Result<ResponseStream<TItem>>;
+}
+
+impl Mediator {
+    /// Registers a stream request handler.
+    pub fn register_stream_handler<TRequest, TStreamRequestHandler, TItem>(
+        &mut self,
+        handler: TStreamRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: StreamRequest<TItem>,
+        TStreamRequestHandler: StreamRequestHandler<TRequest, TItem> + 'static,
+        TItem: 'static,
+    {
+        self.stream_handlers
+            .set::<TRequest, Box<dyn StreamRequestHandler<TRequest, TItem>>>(Box::new(handler));
+        self
+    }
+
+    /// Send a stream request to the mediator.
+    pub async fn send_stream<TRequest, TItem>(
+        &mut self,
+        request: TRequest,
+    ) -> Result<ResponseStream<TItem>>
+    where
+        TRequest: StreamRequest<TItem>,
+        TItem: 'static,
+    {
+        match self
+            .stream_handlers
+            .get_mut::<TRequest, Box<dyn StreamRequestHandler<TRequest, TItem>>>()
+        {
+            Ok(h) => h.handle(request).await,
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MediatorError;
+    use futures::StreamExt;
+
+    #[derive(Debug)]
+    pub struct CountTo(u32);
+
+    impl StreamRequest<u32> for CountTo {}
+
+    #[derive(Debug)]
+    pub struct CountToHandler;
+
+    #[async_trait]
+    impl StreamRequestHandler<CountTo, u32> for CountToHandler {
+        async fn handle(&mut self, request: CountTo) -> Result<ResponseStream<u32>> {
+            Ok(Box::pin(futures::stream::iter((1..=request.0).map(Ok))))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_stream() {
+        let mut m = Mediator::new();
+        m.register_stream_handler(CountToHandler);
+        let mut stream = m.send_stream(CountTo(3)).await.unwrap();
+        let mut items = Vec::new();
+        while let Some(item) = stream.next().await {
+            items.push(item.unwrap());
+        }
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_stream_no_handler_registered() {
+        let mut m = Mediator::new();
+        let err = m.send_stream(CountTo(3)).await.err().unwrap();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<CountTo>().into()
+            ))
+        );
+    }
+}
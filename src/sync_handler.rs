@@ -0,0 +1,99 @@
+use crate::{Mediator, Request, Result};
+
+/// The synchronous request handler trait, for handlers which do not wait on anything,
+/// e.g. pure computations. It is dispatched without an async runtime.
+///
+/// Synchronous handlers are sent requests with `send_sync`, which cannot dispatch to
+/// the async request handlers; likewise, `send` cannot dispatch to synchronous handlers.
+pub trait SyncRequestHandler<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    fn handle(&mut self, request: TRequest) -> Result<TResponse>;
+}
+
+impl Mediator {
+    /// Registers a synchronous request handler.
+    pub fn register_sync_handler<TRequest, TSyncRequestHandler, TResponse>(
+        &mut self,
+        handler: TSyncRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TSyncRequestHandler: SyncRequestHandler<TRequest, TResponse> + 'static,
+        TResponse: 'static,
+    {
+        self.sync_handlers
+            .set::<TRequest, Box<dyn SyncRequestHandler<TRequest, TResponse>>>(Box::new(handler));
+        self
+    }
+
+    /// Send a request to its synchronous request handler.
+    ///
+    /// Synchronous handlers are dispatched without the pipeline behaviors and processors,
+    /// as those are async.
+    pub fn send_sync<TRequest, TResponse>(&mut self, request: TRequest) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: 'static,
+    {
+        match self
+            .sync_handlers
+            .get_mut::<TRequest, Box<dyn SyncRequestHandler<TRequest, TResponse>>>()
+        {
+            Ok(handler) => handler.handle(request),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MediatorError;
+
+    #[derive(Debug)]
+    pub struct Factorial(u64);
+
+    impl Request<u64> for Factorial {}
+
+    #[derive(Debug)]
+    pub struct FactorialHandler;
+
+    impl SyncRequestHandler<Factorial, u64> for FactorialHandler {
+        fn handle(&mut self, request: Factorial) -> Result<u64> {
+            Ok((1..=request.0).product())
+        }
+    }
+
+    #[test]
+    fn test_mediator_send_sync() {
+        let mut m = Mediator::new();
+        m.register_sync_handler(FactorialHandler);
+        assert_eq!(m.send_sync(Factorial(5)).unwrap(), 120);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_and_send_sync_are_separate() {
+        let mut m = Mediator::new();
+        m.register_sync_handler(FactorialHandler);
+        let err = m.send(Factorial(5)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Factorial>().into()
+            ))
+        );
+
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Factorial| async move { Ok(request.0) });
+        let err = m.send_sync(Factorial(5)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerNotRegisteredError(
+                std::any::type_name::<Factorial>().into()
+            ))
+        );
+    }
+}
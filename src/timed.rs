@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+
+use crate::{Mediator, Request, RequestContext, RequestHandler, Result};
+
+/// Wraps a request handler, adding the time spent handling requests to `elapsed`.
+pub(crate) struct TimedHandler<'a, TRequest, TResponse> {
+    handler: &'a mut dyn RequestHandler<TRequest, TResponse>,
+    elapsed: &'a mut Duration,
+}
+
+impl<'a, TRequest, TResponse> TimedHandler<'a, TRequest, TResponse> {
+    pub(crate) fn new(
+        handler: &'a mut dyn RequestHandler<TRequest, TResponse>,
+        elapsed: &'a mut Duration,
+    ) -> Self {
+        TimedHandler { handler, elapsed }
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for TimedHandler<'_, TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        let start = Instant::now();
+        let response = self.handler.handle(request).await;
+        *self.elapsed += start.elapsed();
+        response
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TResponse> {
+        let start = Instant::now();
+        let response = self.handler.handle_with_context(request, context).await;
+        *self.elapsed += start.elapsed();
+        response
+    }
+}
+
+impl Mediator {
+    /// Send a request to the mediator, returning the response with the time spent in the handler.
+    ///
+    /// The duration only covers the request handler, not the lookup, the processors
+    /// and the pipeline behaviors. A behavior running the handler several times
+    /// yields the total time.
+    pub async fn send_timed<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+    ) -> Result<(TResponse, Duration)>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let mut elapsed = Duration::ZERO;
+        let response = self
+            .send_inner(request, &RequestContext::new(), Some(&mut elapsed))
+            .await?;
+        Ok((response, elapsed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Next, PipelineBehavior};
+
+    #[derive(Debug)]
+    pub struct Sleep(Duration);
+
+    impl Request<()> for Sleep {}
+
+    #[derive(Debug)]
+    pub struct SlowBehavior;
+
+    #[async_trait]
+    impl PipelineBehavior<Sleep, ()> for SlowBehavior {
+        async fn handle(&mut self, request: Sleep, mut next: Next<'_, Sleep, ()>) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            next.run(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_timed() {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Sleep| async move {
+            tokio::time::sleep(request.0).await;
+            Ok(())
+        })
+        .register_behavior_for(SlowBehavior);
+        let ((), elapsed) = m
+            .send_timed(Sleep(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        assert!(elapsed >= Duration::from_millis(20));
+        assert!(elapsed < Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,213 @@
+use std::{any::TypeId, collections::HashMap, future::Future, time::Duration};
+
+use crate::{Deadline, Mediator, MediatorError, Request, RequestContext, RequestHandler, Result};
+
+/// The default timeouts of the request handlers, by request type.
+#[derive(Debug, Default)]
+pub(crate) struct DefaultTimeouts(HashMap<TypeId, Duration>);
+
+impl DefaultTimeouts {
+    pub(crate) fn get<TRequest: 'static>(&self) -> Option<Duration> {
+        self.0.get(&TypeId::of::<TRequest>()).copied()
+    }
+
+    pub(crate) fn remove<TRequest: 'static>(&mut self) {
+        self.0.remove(&TypeId::of::<TRequest>());
+    }
+}
+
+/// Runs the future, failing with `MediatorError::TimeoutError` if it does not complete
+/// within the duration.
+pub(crate) async fn timeout<F, T>(duration: Duration, future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match crate::runtime::timeout(duration, future).await {
+        Some(response) => response,
+        None => Err(Box::new(MediatorError::TimeoutError)),
+    }
+}
+
+/// Runs the future within the timeout, if any, and the time remaining until the deadline,
+/// if any, failing with `MediatorError::DeadlineExceededError` once the deadline is exceeded.
+pub(crate) async fn limit<F, T>(
+    duration: Option<Duration>,
+    deadline: Option<Deadline>,
+    future: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let remaining = match deadline {
+        Some(deadline) => deadline.remaining(),
+        None => {
+            return match duration {
+                Some(duration) => timeout(duration, future).await,
+                None => future.await,
+            }
+        }
+    };
+    if remaining.is_zero() {
+        return Err(Box::new(MediatorError::DeadlineExceededError));
+    }
+    match duration {
+        Some(duration) if duration < remaining => timeout(duration, future).await,
+        _ => match crate::runtime::timeout(remaining, future).await {
+            Some(response) => response,
+            None => Err(Box::new(MediatorError::DeadlineExceededError)),
+        },
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler with a default timeout, enforced by `send` and every other
+    /// way of sending a request through the pipeline, e.g. `send_timed` or `send_catch_panic`.
+    /// A request timing out is reported to the error hook and the metrics recorder.
+    ///
+    /// Requests sent with `send_with_timeout` use the given duration instead.
+    /// Registering another handler for the request discards the default timeout.
+    pub fn register_handler_with_timeout<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+        default: Duration,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_handler(handler);
+        self.default_timeouts
+            .0
+            .insert(TypeId::of::<TRequest>(), default);
+        self
+    }
+
+    /// Send a request to the mediator, failing with `MediatorError::TimeoutError`
+    /// if the handler does not complete within the given duration.
+    ///
+    /// The duration overrides the default timeout the handler was registered with, if any.
+    /// It is shortened to the time remaining until the `Deadline` inherited by the request,
+    /// if any, which fails it with `MediatorError::DeadlineExceededError` instead.
+    ///
+    /// This relies on the timer of the runtime selected by the `tokio` or `async-std` feature;
+    /// with tokio, it must be called from within a tokio runtime.
+    pub async fn send_with_timeout<TRequest, TResponse>(
+        &mut self,
+        request: TRequest,
+        duration: Duration,
+    ) -> Result<TResponse>
+    where
+        TRequest: Request<TResponse>,
+        TResponse: Send + 'static,
+    {
+        let context = RequestContext::new();
+        context.set_timeout(duration);
+        self.send_inner(request, &context, None).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Sleep(Duration);
+
+    impl Request<()> for Sleep {}
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: Sleep| async move {
+            tokio::time::sleep(request.0).await;
+            Ok(())
+        });
+        m
+    }
+
+    #[derive(Debug)]
+    pub struct SleepHandler;
+
+    #[async_trait::async_trait]
+    impl RequestHandler<Sleep, ()> for SleepHandler {
+        async fn handle(&mut self, request: Sleep) -> Result<()> {
+            tokio::time::sleep(request.0).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_timeout() {
+        let mut m = mediator();
+        assert!(m
+            .send_with_timeout(Sleep(Duration::ZERO), Duration::from_secs(1))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_timeout_elapsed() {
+        let mut m = mediator();
+        let err = m
+            .send_with_timeout(Sleep(Duration::from_secs(1)), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::TimeoutError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_with_timeout() {
+        let mut m = Mediator::new();
+        m.register_handler_with_timeout(SleepHandler, Duration::from_millis(10));
+        assert!(m.send(Sleep(Duration::ZERO)).await.is_ok());
+        let err = m.send(Sleep(Duration::from_secs(1))).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::TimeoutError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_default_timeout_in_pipeline() {
+        let errors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_errors = errors.clone();
+        let mut m = Mediator::new();
+        m.register_handler_with_timeout(SleepHandler, Duration::from_millis(10))
+            .set_error_hook(move |e| hook_errors.lock().unwrap().push(e.to_string()));
+        let timed_out = |err: Box<dyn std::error::Error + Send + Sync>| {
+            err.downcast_ref::<MediatorError>() == Some(&MediatorError::TimeoutError)
+        };
+        assert!(timed_out(
+            m.send(Sleep(Duration::from_secs(1))).await.unwrap_err()
+        ));
+        assert!(timed_out(
+            m.send_timed(Sleep(Duration::from_secs(1)))
+                .await
+                .unwrap_err()
+        ));
+        assert!(timed_out(
+            m.send_catch_panic(Sleep(Duration::from_secs(1)))
+                .await
+                .unwrap_err()
+        ));
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![MediatorError::TimeoutError.to_string(); 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_send_with_timeout_overrides_default() {
+        let mut m = Mediator::new();
+        m.register_handler_with_timeout(SleepHandler, Duration::from_millis(10));
+        assert!(m
+            .send_with_timeout(Sleep(Duration::from_millis(50)), Duration::from_secs(1))
+            .await
+            .is_ok());
+        m.register_handler(SleepHandler);
+        assert!(m.send(Sleep(Duration::from_millis(50))).await.is_ok());
+    }
+}
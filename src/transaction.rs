@@ -0,0 +1,143 @@
+use crate::{Mediator, MediatorError, Request, RequestHandler};
+
+/// A batch of request handler registrations, applied to the mediator as they are made
+/// and rolled back when the batch is dropped without being committed,
+/// e.g. when building the handlers of a new configuration fails halfway.
+///
+/// Handlers cannot be cloned, so a batch cannot restore a handler it replaced:
+/// it refuses to register a handler for a request which already has one.
+/// The type names of the handlers registered before the batch are returned by
+/// `Mediator::registered_requests`.
+pub struct RegistrationBatch<'a> {
+    mediator: &'a mut Mediator,
+    rollback: Vec<fn(&mut Mediator)>,
+}
+
+fn unregister<TRequest, TResponse>(mediator: &mut Mediator)
+where
+    TRequest: Request<TResponse>,
+    TResponse: 'static,
+{
+    mediator.unregister_handler::<TRequest, TResponse>();
+}
+
+impl RegistrationBatch<'_> {
+    /// Registers a request handler, unless a handler is already registered for the request.
+    ///
+    /// Returns `MediatorError::HandlerAlreadyRegisteredError` otherwise, leaving the batch
+    /// to be rolled back when it is dropped.
+    pub fn register_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        handler: TRequestHandler,
+    ) -> core::result::Result<&mut Self, MediatorError>
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.mediator.try_register_handler(handler)?;
+        self.rollback.push(unregister::<TRequest, TResponse>);
+        Ok(self)
+    }
+
+    /// Returns the mediator, with the registrations made so far.
+    pub fn mediator(&self) -> &Mediator {
+        self.mediator
+    }
+
+    /// Keeps the registrations of the batch.
+    pub fn commit(mut self) {
+        self.rollback.clear();
+    }
+}
+
+impl Drop for RegistrationBatch<'_> {
+    /// Unregisters the handlers registered by the batch, unless it was committed.
+    fn drop(&mut self) {
+        for unregister in self.rollback.drain(..).rev() {
+            unregister(self.mediator);
+        }
+    }
+}
+
+impl std::fmt::Debug for RegistrationBatch<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistrationBatch")
+            .field("registrations", &self.rollback.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Mediator {
+    /// Starts a batch of registrations, which are rolled back unless the batch is committed.
+    pub fn begin_batch(&mut self) -> RegistrationBatch<'_> {
+        RegistrationBatch {
+            mediator: self,
+            rollback: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Result;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    pub struct Ping;
+
+    impl Request<String> for Ping {}
+
+    #[derive(Debug)]
+    pub struct Pong;
+
+    impl Request<String> for Pong {}
+
+    #[derive(Debug)]
+    pub struct Handler(&'static str);
+
+    #[async_trait]
+    impl RequestHandler<Ping, String> for Handler {
+        async fn handle(&mut self, _request: Ping) -> Result<String> {
+            Ok(String::from(self.0))
+        }
+    }
+
+    #[async_trait]
+    impl RequestHandler<Pong, String> for Handler {
+        async fn handle(&mut self, _request: Pong) -> Result<String> {
+            Ok(String::from(self.0))
+        }
+    }
+
+    fn configure(mediator: &mut Mediator) -> core::result::Result<(), MediatorError> {
+        let mut batch = mediator.begin_batch();
+        batch
+            .register_handler::<Pong, _, _>(Handler("new pong"))?
+            .register_handler::<Ping, _, _>(Handler("new ping"))?;
+        batch.commit();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mediator_begin_batch_rollback() {
+        let mut m = Mediator::new();
+        m.register_handler::<Ping, _, _>(Handler("ping"));
+        let before = m.registered_requests();
+        assert_eq!(
+            configure(&mut m),
+            Err(MediatorError::HandlerAlreadyRegisteredError)
+        );
+        assert_eq!(m.registered_requests(), before);
+        assert_eq!(m.send(Ping).await.unwrap(), "ping");
+    }
+
+    #[tokio::test]
+    async fn test_mediator_begin_batch_commit() {
+        let mut m = Mediator::new();
+        assert_eq!(configure(&mut m), Ok(()));
+        assert_eq!(m.send(Ping).await.unwrap(), "new ping");
+        assert_eq!(m.send(Pong).await.unwrap(), "new pong");
+    }
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use std::future::Future;
+
+use crate::{BoxedHandler, Mediator, Request, RequestContext, RequestHandler, Result};
+
+/// The unboxed request handler trait. Unlike the request handler, which returns a boxed future,
+/// it returns its own future type, so calling it directly does not allocate.
+///
+/// The mediator stores its handlers as trait objects, so sending a request through the
+/// mediator still boxes the future once, where a `RequestHandler` boxes it twice.
+/// Invoking the handler directly does not allocate at all.
+/// See `benches/allocations.rs` for the allocation counts and timings.
+///
+/// It is implemented with a native `async fn` in trait, so it needs Rust 1.75 or later,
+/// which is the minimum supported Rust version of the crate. The `RequestHandler` is kept,
+/// based on `async_trait`, for handlers stored and called as trait objects.
+///
+/// A boxed request handler is an unboxed request handler, so code generic over the unboxed
+/// request handler also accepts every request handler once boxed.
+pub trait UnboxedRequestHandler<TRequest, TResponse>: Send + Sync
+where
+    TRequest: Request<TResponse>,
+{
+    /// The method that handles the request.
+    fn handle(&mut self, request: TRequest) -> impl Future<Output = Result<TResponse>> + Send;
+}
+
+impl<TRequest, TResponse> UnboxedRequestHandler<TRequest, TResponse>
+    for BoxedHandler<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    fn handle(&mut self, request: TRequest) -> impl Future<Output = Result<TResponse>> + Send {
+        RequestHandler::handle(&mut **self, request)
+    }
+}
+
+struct UnboxedHandler<TUnboxedRequestHandler>(TUnboxedRequestHandler);
+
+#[async_trait]
+impl<TRequest, TUnboxedRequestHandler, TResponse> RequestHandler<TRequest, TResponse>
+    for UnboxedHandler<TUnboxedRequestHandler>
+where
+    TRequest: Request<TResponse>,
+    TUnboxedRequestHandler: UnboxedRequestHandler<TRequest, TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        self.0.handle(request).await
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        _context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.0.handle(request).await
+    }
+}
+
+impl Mediator {
+    /// Registers an unboxed request handler.
+    pub fn register_unboxed_handler<TRequest, TUnboxedRequestHandler, TResponse>(
+        &mut self,
+        handler: TUnboxedRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TUnboxedRequestHandler: UnboxedRequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        self.register_handler(UnboxedHandler(handler))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Add(i64, i64);
+
+    impl Request<i64> for Add {}
+
+    #[derive(Debug)]
+    pub struct AddHandler;
+
+    impl UnboxedRequestHandler<Add, i64> for AddHandler {
+        async fn handle(&mut self, request: Add) -> Result<i64> {
+            Ok(request.0 + request.1)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unboxed_request_handler() {
+        assert_eq!(AddHandler.handle(Add(40, 2)).await.unwrap(), 42);
+    }
+
+    #[derive(Debug)]
+    pub struct BoxedAddHandler;
+
+    #[async_trait]
+    impl RequestHandler<Add, i64> for BoxedAddHandler {
+        async fn handle(&mut self, request: Add) -> Result<i64> {
+            Ok(request.0 + request.1)
+        }
+    }
+
+    async fn add<THandler>(handler: &mut THandler, a: i64, b: i64) -> Result<i64>
+    where
+        THandler: UnboxedRequestHandler<Add, i64>,
+    {
+        handler.handle(Add(a, b)).await
+    }
+
+    #[tokio::test]
+    async fn test_boxed_handler_is_unboxed_request_handler() {
+        let mut handler: BoxedHandler<Add, i64> = Box::new(BoxedAddHandler);
+        assert_eq!(add(&mut handler, 40, 2).await.unwrap(), 42);
+        assert_eq!(add(&mut AddHandler, 40, 2).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_unboxed_handler() {
+        let mut m = Mediator::new();
+        m.register_unboxed_handler(AddHandler);
+        assert_eq!(m.send(Add(40, 2)).await.unwrap(), 42);
+    }
+}
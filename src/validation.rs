@@ -0,0 +1,252 @@
+use async_trait::async_trait;
+
+use crate::{MediatorError, Next, PipelineBehavior, Request, Result};
+
+/// A validation failure, describing why a field of a request is invalid.
+///
+/// The code identifies the kind of failure, e.g. for an API layer to map it
+/// to a localized message, while the message is meant to be read as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidationFailure {
+    /// The path of the invalid field, with the names of nested fields separated by dots,
+    /// e.g. `address.zip`.
+    pub path: String,
+    /// The code of the failure, e.g. `required`.
+    pub code: String,
+    /// The reason why the field is invalid.
+    pub message: String,
+}
+
+impl ValidationFailure {
+    /// Creates a new validation failure.
+    pub fn new(
+        path: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        ValidationFailure {
+            path: path.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Prefixes the path with the name of the parent field,
+    /// e.g. to report the failures of a nested validator.
+    pub fn nested_in(mut self, parent: &str) -> Self {
+        self.path = if self.path.is_empty() {
+            String::from(parent)
+        } else {
+            format!("{}.{}", parent, self.path)
+        };
+        self
+    }
+}
+
+impl std::fmt::Display for ValidationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} ({})", self.path, self.message, self.code)
+    }
+}
+
+/// The validator trait. A validator checks a request before it is handled.
+#[async_trait]
+pub trait Validator<TRequest>: Send + Sync
+where
+    TRequest: Send + Sync + 'static,
+{
+    /// The method that validates the request.
+    async fn validate(
+        &self,
+        request: &TRequest,
+    ) -> core::result::Result<(), Vec<ValidationFailure>>;
+}
+
+/// A pipeline behavior that validates requests before they are handled.
+///
+/// Every validator runs, and all their failures are returned as `MediatorError::ValidationError`.
+pub struct ValidationBehavior<TRequest> {
+    validators: Vec<Box<dyn Validator<TRequest>>>,
+}
+
+impl<TRequest> ValidationBehavior<TRequest>
+where
+    TRequest: Send + Sync + 'static,
+{
+    /// Creates a new validation behavior without any validator.
+    pub fn new() -> Self {
+        ValidationBehavior {
+            validators: Vec::new(),
+        }
+    }
+
+    /// Adds a validator.
+    pub fn with_validator<TValidator>(mut self, validator: TValidator) -> Self
+    where
+        TValidator: Validator<TRequest> + 'static,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+}
+
+impl<TRequest> Default for ValidationBehavior<TRequest>
+where
+    TRequest: Send + Sync + 'static,
+{
+    fn default() -> Self {
+        ValidationBehavior::new()
+    }
+}
+
+impl<TRequest> std::fmt::Debug for ValidationBehavior<TRequest> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationBehavior")
+            .field("validators", &self.validators.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for ValidationBehavior<TRequest>
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(
+        &mut self,
+        request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let mut failures = Vec::new();
+        for validator in self.validators.iter() {
+            if let Err(mut f) = validator.validate(&request).await {
+                failures.append(&mut f);
+            }
+        }
+        if !failures.is_empty() {
+            return Err(Box::new(MediatorError::ValidationError(failures)));
+        }
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+
+    #[derive(Debug)]
+    pub struct CreateUser {
+        name: String,
+        age: u32,
+    }
+
+    impl Request<String> for CreateUser {}
+
+    #[derive(Debug)]
+    pub struct NameValidator;
+
+    #[async_trait]
+    impl Validator<CreateUser> for NameValidator {
+        async fn validate(
+            &self,
+            request: &CreateUser,
+        ) -> core::result::Result<(), Vec<ValidationFailure>> {
+            if request.name.is_empty() {
+                return Err(vec![ValidationFailure::new(
+                    "name",
+                    "required",
+                    "must not be empty",
+                )]);
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AgeValidator;
+
+    #[async_trait]
+    impl Validator<CreateUser> for AgeValidator {
+        async fn validate(
+            &self,
+            request: &CreateUser,
+        ) -> core::result::Result<(), Vec<ValidationFailure>> {
+            if request.age < 18 {
+                return Err(vec![ValidationFailure::new(
+                    "age",
+                    "min",
+                    "must be at least 18",
+                )]);
+            }
+            Ok(())
+        }
+    }
+
+    fn mediator() -> Mediator {
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: CreateUser| async move { Ok(request.name) })
+            .register_behavior_for(
+                ValidationBehavior::new()
+                    .with_validator(NameValidator)
+                    .with_validator(AgeValidator),
+            );
+        m
+    }
+
+    #[tokio::test]
+    async fn test_validation_behavior_valid_request() {
+        let mut m = mediator();
+        let request = CreateUser {
+            name: String::from("john"),
+            age: 42,
+        };
+        assert_eq!(m.send(request).await.unwrap(), "john");
+    }
+
+    #[tokio::test]
+    async fn test_validation_behavior_collects_failures() {
+        let mut m = mediator();
+        let request = CreateUser {
+            name: String::new(),
+            age: 12,
+        };
+        let err = m.send(request).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::ValidationError(vec![
+                ValidationFailure::new("name", "required", "must not be empty"),
+                ValidationFailure::new("age", "min", "must be at least 18"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_validation_error_display() {
+        let error = MediatorError::ValidationError(vec![
+            ValidationFailure::new("zip", "format", "must be 5 digits").nested_in("address"),
+            ValidationFailure::new("", "required", "must not be empty").nested_in("name"),
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "Validation failed; address.zip: must be 5 digits (format); \
+             name: must not be empty (required)"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_validation_failure_serde() {
+        let failure = ValidationFailure::new("address.zip", "format", "must be 5 digits");
+        let json = serde_json::to_string(&failure).unwrap();
+        assert_eq!(
+            json,
+            r#"{"path":"address.zip","code":"format","message":"must be 5 digits"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ValidationFailure>(&json).unwrap(),
+            failure
+        );
+    }
+}
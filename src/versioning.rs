@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+use crate::{MediatorError, Next, PipelineBehavior, Request, Result};
+
+/// The versioned trait. Requests implementing it opt in to the versioning behavior.
+pub trait Versioned {
+    /// Returns the schema version the request was produced with.
+    fn version(&self) -> u32;
+}
+
+type Upcaster<TRequest> = Box<dyn Fn(TRequest) -> TRequest + Send + Sync>;
+
+/// A pipeline behavior rejecting requests of an unsupported schema version,
+/// and upcasting the older ones, so handlers only deal with the latest shape of the requests.
+///
+/// Requests of a version below the minimum fail with `MediatorError::UnsupportedVersionError`.
+/// The other requests go through the upcasters, each registered for the version it upgrades
+/// from: the upcaster registered for the version of the request is applied, then the one
+/// registered for the version it returns, and so on, until no upcaster is registered for the
+/// version of the request. An upcaster must return a request of a newer version, otherwise
+/// the upcasting stops there.
+pub struct VersioningBehavior<TRequest> {
+    minimum_version: u32,
+    upcasters: BTreeMap<u32, Upcaster<TRequest>>,
+}
+
+impl<TRequest> VersioningBehavior<TRequest> {
+    /// Creates a new versioning behavior, rejecting the requests below the minimum version.
+    pub fn new(minimum_version: u32) -> Self {
+        VersioningBehavior {
+            minimum_version,
+            upcasters: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the upcaster upgrading the requests of the given version to a newer one,
+    /// e.g. filling a field added in the next version with a default value.
+    ///
+    /// Registering another upcaster for the same version replaces the previous one.
+    pub fn with_upcaster(
+        mut self,
+        version: u32,
+        upcaster: impl Fn(TRequest) -> TRequest + Send + Sync + 'static,
+    ) -> Self {
+        self.upcasters.insert(version, Box::new(upcaster));
+        self
+    }
+}
+
+impl<TRequest> std::fmt::Debug for VersioningBehavior<TRequest> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VersioningBehavior")
+            .field("minimum_version", &self.minimum_version)
+            .field("upcasters", &self.upcasters.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> PipelineBehavior<TRequest, TResponse> for VersioningBehavior<TRequest>
+where
+    TRequest: Request<TResponse> + Versioned,
+    TResponse: Send + 'static,
+{
+    async fn handle(
+        &mut self,
+        mut request: TRequest,
+        mut next: Next<'_, TRequest, TResponse>,
+    ) -> Result<TResponse> {
+        let mut version = request.version();
+        if version < self.minimum_version {
+            return Err(Box::new(MediatorError::UnsupportedVersionError(version)));
+        }
+        while let Some(upcaster) = self.upcasters.get(&version) {
+            request = upcaster(request);
+            match request.version() {
+                upcast if upcast > version => version = upcast,
+                _ => break,
+            }
+        }
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mediator;
+
+    #[derive(Debug)]
+    pub struct PlaceOrder {
+        version: u32,
+        quantity: u32,
+        currency: Option<&'static str>,
+    }
+
+    impl Request<String> for PlaceOrder {}
+
+    impl Versioned for PlaceOrder {
+        fn version(&self) -> u32 {
+            self.version
+        }
+    }
+
+    fn order(version: u32, quantity: u32, currency: Option<&'static str>) -> PlaceOrder {
+        PlaceOrder {
+            version,
+            quantity,
+            currency,
+        }
+    }
+
+    fn mediator() -> Mediator {
+        let behavior = VersioningBehavior::new(2)
+            .with_upcaster(2, |request: PlaceOrder| PlaceOrder {
+                version: 3,
+                currency: Some("EUR"),
+                ..request
+            })
+            .with_upcaster(3, |request: PlaceOrder| PlaceOrder {
+                version: 4,
+                quantity: request.quantity.max(1),
+                ..request
+            });
+        let mut m = Mediator::new();
+        m.register_handler_fn(|request: PlaceOrder| async move {
+            Ok(format!(
+                "v{}: {} {}",
+                request.version,
+                request.quantity,
+                request.currency.unwrap()
+            ))
+        })
+        .register_behavior_for::<PlaceOrder, String, _>(behavior);
+        m
+    }
+
+    #[tokio::test]
+    async fn test_versioning_behavior_upcasts_older_versions() {
+        let mut m = mediator();
+        assert_eq!(m.send(order(2, 0, None)).await.unwrap(), "v4: 1 EUR");
+        assert_eq!(m.send(order(3, 5, Some("USD"))).await.unwrap(), "v4: 5 USD");
+        assert_eq!(m.send(order(4, 0, Some("USD"))).await.unwrap(), "v4: 0 USD");
+    }
+
+    #[tokio::test]
+    async fn test_versioning_behavior_rejects_unsupported_versions() {
+        let mut m = mediator();
+        let err = m.send(order(1, 1, None)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::UnsupportedVersionError(1))
+        );
+        assert_eq!(err.to_string(), "Unsupported request version 1");
+    }
+}
@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use rand::Rng;
+use std::sync::Arc;
+
+use crate::{Mediator, MediatorError, Request, RequestContext, RequestHandler, Result};
+
+/// The request handlers registered with a weight for a request type, in registration order.
+pub(crate) struct WeightedHandlers<TRequest, TResponse> {
+    handlers: Vec<(u32, Box<dyn RequestHandler<TRequest, TResponse>>)>,
+    total_weight: u64,
+}
+
+pub(crate) type SharedWeightedHandlers<TRequest, TResponse> =
+    Arc<Mutex<WeightedHandlers<TRequest, TResponse>>>;
+
+impl<TRequest, TResponse> WeightedHandlers<TRequest, TResponse> {
+    fn select(&mut self) -> Result<&mut Box<dyn RequestHandler<TRequest, TResponse>>> {
+        if self.total_weight == 0 {
+            return Err(Box::new(MediatorError::HandlerUnavailableError));
+        }
+        let mut pick = rand::thread_rng().gen_range(0..self.total_weight);
+        for (weight, handler) in self.handlers.iter_mut() {
+            match pick.checked_sub(u64::from(*weight)) {
+                Some(rest) => pick = rest,
+                None => return Ok(handler),
+            }
+        }
+        unreachable!("the pick is lower than the total weight")
+    }
+}
+
+#[async_trait]
+impl<TRequest, TResponse> RequestHandler<TRequest, TResponse>
+    for WeightedHandlers<TRequest, TResponse>
+where
+    TRequest: Request<TResponse>,
+{
+    async fn handle(&mut self, request: TRequest) -> Result<TResponse> {
+        self.select()?.handle(request).await
+    }
+
+    async fn handle_with_context(
+        &mut self,
+        request: TRequest,
+        context: &RequestContext,
+    ) -> Result<TResponse> {
+        self.select()?.handle_with_context(request, context).await
+    }
+
+    async fn on_shutdown(&mut self) {
+        for (_, handler) in self.handlers.iter_mut() {
+            handler.on_shutdown().await;
+        }
+    }
+}
+
+impl Mediator {
+    /// Registers a request handler with a weight, e.g. to send a share of the traffic
+    /// to a canary handler.
+    ///
+    /// Several handlers can be registered with a weight for the same request type:
+    /// each request is handled by one of them, picked at random in proportion to its weight.
+    /// A handler with a weight of 0 is never picked. If the weights add up to 0, the requests
+    /// fail with `MediatorError::HandlerUnavailableError`.
+    /// Registering a handler with `register_handler` replaces every weighted handler.
+    pub fn register_weighted_handler<TRequest, TRequestHandler, TResponse>(
+        &mut self,
+        weight: u32,
+        handler: TRequestHandler,
+    ) -> &mut Self
+    where
+        TRequest: Request<TResponse>,
+        TRequestHandler: RequestHandler<TRequest, TResponse> + 'static,
+        TResponse: Send + 'static,
+    {
+        let handlers = match self
+            .weighted_handlers
+            .remove::<TRequest, SharedWeightedHandlers<TRequest, TResponse>>()
+        {
+            Some(handlers) => handlers,
+            None => Arc::new(Mutex::new(WeightedHandlers {
+                handlers: Vec::new(),
+                total_weight: 0,
+            })),
+        };
+        {
+            let mut guard = handlers
+                .try_lock()
+                .expect("the handlers are only locked while handling a request");
+            guard.handlers.push((weight, Box::new(handler)));
+            guard.total_weight += u64::from(weight);
+        }
+        self.register_handler(handlers.clone());
+        self.weighted_handlers
+            .set::<TRequest, SharedWeightedHandlers<TRequest, TResponse>>(handlers);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Checkout;
+
+    impl Request<&'static str> for Checkout {}
+
+    #[derive(Debug)]
+    pub struct VariantHandler(&'static str);
+
+    #[async_trait]
+    impl RequestHandler<Checkout, &'static str> for VariantHandler {
+        async fn handle(&mut self, _request: Checkout) -> Result<&'static str> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_weighted_handler() {
+        let mut m = Mediator::new();
+        m.register_weighted_handler(3, VariantHandler("stable"))
+            .register_weighted_handler(1, VariantHandler("canary"))
+            .register_weighted_handler(0, VariantHandler("disabled"));
+        let mut canary = 0;
+        for _ in 0..4000 {
+            match m.send(Checkout).await.unwrap() {
+                "stable" => {}
+                "canary" => canary += 1,
+                variant => panic!("unexpected variant {}", variant),
+            }
+        }
+        assert!((700..1300).contains(&canary), "{} canary requests", canary);
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_weighted_handler_zero_total_weight() {
+        let mut m = Mediator::new();
+        m.register_weighted_handler(0, VariantHandler("disabled"));
+        let err = m.send(Checkout).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MediatorError>(),
+            Some(&MediatorError::HandlerUnavailableError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mediator_register_handler_replaces_weighted_handlers() {
+        let mut m = Mediator::new();
+        m.register_weighted_handler(1, VariantHandler("canary"))
+            .register_handler(VariantHandler("default"))
+            .register_weighted_handler(1, VariantHandler("stable"));
+        for _ in 0..10 {
+            assert_eq!(m.send(Checkout).await.unwrap(), "stable");
+        }
+    }
+}